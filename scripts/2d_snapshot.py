@@ -13,9 +13,9 @@ Y = np.linspace(0, deserialized['y_sz'], deserialized['y_sz'])
 X, Y = np.meshgrid(X, Y)
 
 Z = np.array(deserialized['ez'])
-# Convert from 1D to 2D.
-# TODO: Shouldn't 'y_sz' and 'x_sz' be flipped?
-Z = Z.reshape(deserialized['y_sz'], deserialized['x_sz'])
+# Convert from 1D to 2D. Nodes are stored x-major, the node at (x, y) at
+# 'x * y_sz + y', while the mesh is indexed by row (y) then column (x).
+Z = Z.reshape(deserialized['x_sz'], deserialized['y_sz']).T
 
 fig, ax = plt.subplots()
 ax.pcolormesh(X, Y, Z, vmin=-3.0, vmax=0)
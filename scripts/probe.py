@@ -0,0 +1,20 @@
+#!/usr/bin/python
+# Plot the time series recorded by a probe, as written by 'Probe::write_csv'.
+import csv
+import sys
+import matplotlib.pyplot as plt
+
+f = open(sys.argv[1])
+rows = list(csv.reader(f))
+header, rows = rows[0], rows[1:]
+
+plt.title("Probe: {}".format(sys.argv[1]))
+plt.xlabel("Time [step]")
+plt.ylabel("Field")
+
+steps = [int(r[0]) for r in rows]
+for (i, name) in enumerate(header[1:]):
+    plt.plot(steps, [float(r[i + 1]) for r in rows], label=name)
+
+plt.legend()
+plt.show()
@@ -1,5 +1,7 @@
 // src/abc.rs
-use crate::grid::Grid;
+use crate::fdtd::{GridDimension, Polarization};
+use crate::grid::{Field, Grid};
+use std::collections::HashMap;
 
 // 1st order 1D advection ABC.
 pub fn advection_abc_1st_order(cezh: &[f64], chye: &[f64]) -> impl FnMut(usize, &mut Grid) {
@@ -75,3 +77,245 @@ pub fn advection_abc_2nd_order(cezh: &[f64], chye: &[f64]) -> impl FnMut(usize,
 
     f
 }
+
+// Tangential electric components handled by the Mur ABCs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Component {
+    Ex,
+    Ey,
+    Ez,
+}
+
+impl Component {
+    // Axis the component points along.
+    fn axis(self) -> usize {
+        match self {
+            Component::Ex => 0,
+            Component::Ey => 1,
+            Component::Ez => 2,
+        }
+    }
+
+    fn field(self, g: &mut Grid) -> &mut Vec<f64> {
+        match self {
+            Component::Ex => &mut g.ex,
+            Component::Ey => &mut g.ey,
+            Component::Ez => &mut g.ez,
+        }
+    }
+
+    // Local Courant number at boundary node 'b' with inward neighbour 'i',
+    // on a face normal to axis 'a'. A wave leaving through the face couples
+    // the component to the magnetic component along the remaining axis.
+    fn courant(self, g: &Grid, a: usize, b: usize, i: usize) -> f64 {
+        let (_, ce) = g.coefficients(Field::electric(self.axis()));
+        let (_, ch) = g.coefficients(Field::magnetic(3 - a - self.axis()));
+        (ce[b] * ch[b.min(i)]).sqrt()
+    }
+}
+
+// A tangential electric node on a face of the grid.
+struct MurNode {
+    component: Component,
+
+    // Index of the node on the face, and of its neighbour one cell inward.
+    b: usize,
+    i: usize,
+
+    // (S - 1) / (S + 1), 2 / (S + 1) and S^2 / (2 * (S + 1)), where S is
+    // the local Courant number.
+    coef: [f64; 3],
+
+    // Positions within the node list of the transverse neighbours on either
+    // side; None if the node lies on an edge of the face. Past either end of
+    // a row along the component's own axis the neighbour is a ghost node,
+    // found at the node's own position offset by the length of the list.
+    neighbours: Option<Vec<[usize; 2]>>,
+}
+
+// Find every tangential electric node on the faces of the grid. Nodes on
+// edges shared by two faces the component is tangential to are left alone.
+fn mur_nodes(g: &Grid, dimension: GridDimension) -> Vec<MurNode> {
+    let (components, axes): (&[Component], usize) = match dimension {
+        GridDimension::Two(Polarization::Magnetic) => (&[Component::Ez], 2),
+        GridDimension::Two(Polarization::Electric) => (&[Component::Ex, Component::Ey], 2),
+        GridDimension::Three => (&[Component::Ex, Component::Ey, Component::Ez], 3),
+        GridDimension::One => panic!("Unimplemented!"),
+    };
+
+    let sz = [g.x_sz, g.y_sz, if axes == 3 { g.z_sz } else { 1 }];
+    let stride = [sz[1] * sz[2], sz[2], 1];
+    let index = |c: [usize; 3]| (c[0] * sz[1] + c[1]) * sz[2] + c[2];
+
+    // Range covered along axis 't' by a component on a face normal to 'a'.
+    // Components sit half a cell along their own axis, so the last node
+    // along it lies outside the grid.
+    let range = |c: Component, a: usize, t: usize| {
+        if t == a || sz[t] == 1 {
+            0..1
+        } else if t == c.axis() {
+            0..sz[t] - 1
+        } else {
+            1..sz[t] - 1
+        }
+    };
+
+    let mut nodes = Vec::new();
+    let mut coords = Vec::new();
+    for &c in components {
+        for a in (0..axes).filter(|a| *a != c.axis()) {
+            for hi in [false, true] {
+                for x in range(c, a, 0) {
+                    for y in range(c, a, 1) {
+                        for z in range(c, a, 2) {
+                            let mut pos = [x, y, z];
+                            if hi {
+                                pos[a] = sz[a] - 1;
+                            }
+
+                            let b = index(pos);
+                            let i = if hi { b - stride[a] } else { b + stride[a] };
+                            let s = c.courant(g, a, b, i);
+
+                            nodes.push(MurNode {
+                                component: c,
+                                b,
+                                i,
+                                coef: [
+                                    (s - 1.0) / (s + 1.0),
+                                    2.0 / (s + 1.0),
+                                    s * s / (2.0 * (s + 1.0)),
+                                ],
+                                neighbours: None,
+                            });
+                            coords.push((a, pos));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Link each node to its transverse neighbours on the same face. Along
+    // its own axis a component runs right up to the edge of the face, so
+    // the end nodes are closed with a ghost neighbour rather than dropping
+    // to 1st order. Along the remaining axes the end nodes sit on an edge.
+    let n = nodes.len();
+    let lookup: HashMap<(Component, usize), usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(k, n)| ((n.component, n.b), k))
+        .collect();
+
+    for (k, (a, pos)) in coords.into_iter().enumerate() {
+        let c = nodes[k].component;
+        let mut neighbours = Vec::new();
+
+        for t in (0..axes).filter(|t| *t != a && sz[*t] > 1) {
+            let r = range(c, a, t);
+            let (first, last) = (pos[t] == r.start, pos[t] + 1 == r.end);
+            if (first || last) && t != c.axis() {
+                neighbours.clear();
+                break;
+            }
+
+            let b = nodes[k].b;
+            let l = if first {
+                n + k
+            } else {
+                lookup[&(c, b - stride[t])]
+            };
+            let r = if last {
+                n + k
+            } else {
+                lookup[&(c, b + stride[t])]
+            };
+            neighbours.push([l, r]);
+        }
+
+        if !neighbours.is_empty() {
+            nodes[k].neighbours = Some(neighbours);
+        }
+    }
+
+    nodes
+}
+
+// 1st order Mur ABC for the tangential electric fields on every face of a
+// 2D or 3D grid.
+pub fn mur_abc_1st_order(g: &Grid, dimension: GridDimension) -> impl FnMut(usize, &mut Grid) {
+    let nodes = mur_nodes(g, dimension);
+
+    // Boundary and inward nodes at the previous time step.
+    let mut old0 = vec![0.0; nodes.len()];
+    let mut old1 = vec![0.0; nodes.len()];
+
+    let f = move |_: usize, g: &mut Grid| {
+        for (k, node) in nodes.iter().enumerate() {
+            let e = node.component.field(g);
+
+            e[node.b] = old1[k] + node.coef[0] * (e[node.i] - old0[k]);
+            old0[k] = e[node.b];
+            old1[k] = e[node.i];
+        }
+    };
+
+    f
+}
+
+// 2nd order Mur ABC for the tangential electric fields on every face of a
+// 2D or 3D grid. Nodes on the edges of a face fall back to 1st order.
+pub fn mur_abc_2nd_order(g: &Grid, dimension: GridDimension) -> impl FnMut(usize, &mut Grid) {
+    let nodes = mur_nodes(g, dimension);
+
+    // Boundary and inward nodes one and two time steps back. The ghost
+    // nodes past the ends of each row follow the boundary and inward nodes,
+    // advanced with the 1st order condition along the face.
+    let n = nodes.len();
+    let mut old0 = vec![0.0; 2 * n];
+    let mut old1 = vec![0.0; 2 * n];
+    let mut older0 = vec![0.0; n];
+    let mut older1 = vec![0.0; n];
+    let mut next = vec![0.0; n];
+
+    let f = move |_: usize, g: &mut Grid| {
+        // Transverse derivatives read the old values of neighbouring nodes,
+        // so compute every node before writing any back.
+        for (k, node) in nodes.iter().enumerate() {
+            let e = node.component.field(g);
+
+            next[k] = match &node.neighbours {
+                None => old1[k] + node.coef[0] * (e[node.i] - old0[k]),
+                Some(neighbours) => {
+                    let transverse: f64 = neighbours
+                        .iter()
+                        .map(|[l, r]| {
+                            old0[*l] - 2.0 * old0[k] + old0[*r] + old1[*l] - 2.0 * old1[k]
+                                + old1[*r]
+                        })
+                        .sum();
+
+                    -older1[k]
+                        + node.coef[0] * (e[node.i] + older0[k])
+                        + node.coef[1] * (old0[k] + old1[k])
+                        + node.coef[2] * transverse
+                }
+            };
+        }
+
+        for (k, node) in nodes.iter().enumerate() {
+            let e = node.component.field(g);
+            e[node.b] = next[k];
+
+            old0[n + k] = old0[k] + node.coef[0] * (e[node.b] - old0[n + k]);
+            old1[n + k] = old1[k] + node.coef[0] * (e[node.i] - old1[n + k]);
+
+            older0[k] = old0[k];
+            older1[k] = old1[k];
+            old0[k] = e[node.b];
+            old1[k] = e[node.i];
+        }
+    };
+
+    f
+}
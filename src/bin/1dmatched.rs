@@ -2,63 +2,28 @@
 // layer which matches the impedance of the dielectric. Rust port of
 // the 'Program 3.8'.
 use fdtd::fdtd::{FDTDSim, GridDimension};
-use fdtd::grid::{Grid, IMP0};
+use fdtd::grid::{Field, Grid, IMP0};
+use fdtd::material::Material;
+use fdtd::probe::{Location, Probe};
 use fdtd::snapshot;
+use fdtd::tfsf::Tfsf1d;
+use fdtd::waveform::Gaussian;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 const SIZE: usize = 200;
+const EPSR: f64 = 9.0;
 const LOSS: f64 = 0.02;
 const LOSS_LAYER: usize = 180;
 
 fn main() {
-    // Build the electric field update coefficients.
-    let mut ceze = Vec::with_capacity(SIZE);
-    let mut cezh = Vec::with_capacity(SIZE);
-    ceze.resize(SIZE, 0.0);
-    cezh.resize(SIZE, 0.0);
-    for mm in 0..SIZE {
-        if mm < 100 {
-            ceze[mm] = 1.0;
-            cezh[mm] = IMP0;
-        } else if mm < LOSS_LAYER {
-            ceze[mm] = 1.0;
-            cezh[mm] = IMP0 / 9.0;
-        } else {
-            ceze[mm] = (1.0 - LOSS) / (1.0 + LOSS);
-            cezh[mm] = IMP0 / 9.0 / (1.0 + LOSS);
-        }
-    }
-
-    // Build the magnetic field update coefficients.
-    let mut chyh = Vec::with_capacity(SIZE);
-    let mut chye = Vec::with_capacity(SIZE);
-    chyh.resize(SIZE, 0.0);
-    chye.resize(SIZE, 0.0);
-    for mm in 0..SIZE - 1 {
-        if mm < LOSS_LAYER {
-            chyh[mm] = 1.0;
-            chye[mm] = 1.0 / IMP0;
-        } else {
-            chyh[mm] = (1.0 - LOSS) / (1.0 + LOSS);
-            chye[mm] = 1.0 / IMP0 / (1.0 + LOSS);
-        }
-    }
-
     // ABC for ez[0].
     let abc_ez_fn = |g: &mut Grid| {
         g.ez[0] = g.ez[1];
     };
 
-    // TFSF for Hy adjacent to TFSF boundary.
-    let hy_tfsf_fn = |t: usize, g: &mut Grid| {
-        let t = t as f64;
-        g.hy[49] -= (-(t - 30.0) * (t - 30.0) / 100.0).exp() / IMP0;
-    };
-
-    // TFSF for Ez adjacent to TFSF boundary.
-    let ez_tfsf_fn = |t: usize, g: &mut Grid| {
-        let t = t as f64;
-        g.ez[50] += (-(t + 0.5 - (-0.5) - 30.0) * (t + 0.5 - (-0.5) - 30.0) / 100.0).exp();
-    };
+    // TFSF boundary at ez[50], introducing a Gaussian pulse.
+    let tfsf = Tfsf1d::new(50, Gaussian::new(30.0, 10.0), Material::VACUUM).unwrap();
 
     // Snapshot setup.
     let fdir = snapshot::create_output_dir().unwrap();
@@ -66,22 +31,32 @@ fn main() {
 
     // Build the post magnetic/electric functions.
     let post_magnetic = |t: usize, g: &mut Grid| {
-        hy_tfsf_fn(t, g);
+        tfsf.magnetic(t, g);
         abc_ez_fn(g);
     };
 
     let post_electric = |t: usize, g: &mut Grid| {
-        ez_tfsf_fn(t, g);
+        tfsf.electric(t, g);
         if t % 10 == 0 {
             snapshot_fn(t / 10, g).unwrap();
         }
     };
 
+    // Vacuum, then a dielectric, then a lossy layer matched to it. The
+    // conductivity gives a loss of 'LOSS' per time step.
+    let dielectric = Material::dielectric(EPSR);
+    let lossy = Material::matched(EPSR, 1.0, 2.0 * EPSR * LOSS / IMP0);
+
     let mut g = Grid::new_1d(SIZE);
-    g.ceze = ceze;
-    g.cezh = cezh;
-    g.chyh = chyh;
-    g.chye = chye;
+    g.set_materials(|[mm, _, _]| {
+        if mm < 100 {
+            Material::VACUUM
+        } else if mm < LOSS_LAYER {
+            dielectric
+        } else {
+            lossy
+        }
+    });
 
     // Create the FDTDSim.
     let mut fdtd_sim = FDTDSim::new(
@@ -96,7 +71,17 @@ fn main() {
     fdtd_sim.set_post_magnetic(Some(post_magnetic));
     fdtd_sim.set_post_electric(Some(post_electric));
 
+    // Record the waveform either side of the interface and within the
+    // lossy layer, written alongside the snapshots.
+    let nodes = [90, 110, 190].map(|x| Location::Node([x, 0, 0]));
+    let probe = Rc::new(RefCell::new(
+        Probe::new(&g, &[Field::Ez], &nodes, 1).unwrap(),
+    ));
+    fdtd_sim.add_observer(probe.clone());
+
     for _ in 0..450 {
         fdtd_sim.step(&mut g).unwrap();
     }
+
+    probe.borrow().write_csv(&format!("{}.csv", fdir)).unwrap();
 }
@@ -1,51 +1,30 @@
 // Rust port of the 1st order ABC demo 'Program 6.2'.
 use fdtd::abc;
 use fdtd::fdtd::{FDTDSim, GridDimension};
-use fdtd::grid::{Grid, IMP0};
+use fdtd::grid::Grid;
+use fdtd::material::Material;
 use fdtd::snapshot;
+use fdtd::tfsf::Tfsf1d;
+use fdtd::waveform::Gaussian;
 
 const SIZE: usize = 200;
 const EPSR: f64 = 9.0;
 
 fn main() {
-    // Build the electric field update coefficients.
-    let mut ceze = Vec::with_capacity(SIZE);
-    let mut cezh = Vec::with_capacity(SIZE);
-    ceze.resize(SIZE, 0.0);
-    cezh.resize(SIZE, 0.0);
-    for mm in 0..SIZE {
+    let mut g = Grid::new_1d(SIZE);
+    let dielectric = Material::dielectric(EPSR);
+    g.set_materials(|[mm, _, _]| {
         if mm < 100 {
-            ceze[mm] = 1.0;
-            cezh[mm] = IMP0;
+            Material::VACUUM
         } else {
-            ceze[mm] = 1.0;
-            cezh[mm] = IMP0 / EPSR;
+            dielectric
         }
-    }
+    });
 
-    // Build the magnetic field update coefficients.
-    let mut chyh = Vec::with_capacity(SIZE);
-    let mut chye = Vec::with_capacity(SIZE);
-    chyh.resize(SIZE, 0.0);
-    chye.resize(SIZE, 0.0);
-    for mm in 0..SIZE - 1 {
-        chyh[mm] = 1.0;
-        chye[mm] = 1.0 / IMP0;
-    }
+    let mut abc_fn = abc::advection_abc_1st_order(&g.cezh, &g.chye);
 
-    let mut abc_fn = abc::advection_abc_1st_order(&cezh, &chye);
-
-    // TFSF for Hy adjacent to TFSF boundary.
-    let hy_tfsf_fn = |t: usize, g: &mut Grid| {
-        let t = t as f64;
-        g.hy[49] -= (-(t - 30.0) * (t - 30.0) / 100.0).exp() / IMP0;
-    };
-
-    // TFSF for Ez adjacent to TFSF boundary.
-    let ez_tfsf_fn = |t: usize, g: &mut Grid| {
-        let t = t as f64;
-        g.ez[50] += (-(t + 0.5 - (-0.5) - 30.0) * (t + 0.5 - (-0.5) - 30.0) / 100.0).exp();
-    };
+    // TFSF boundary at ez[50], introducing a Gaussian pulse.
+    let tfsf = Tfsf1d::new(50, Gaussian::new(30.0, 10.0), Material::VACUUM).unwrap();
 
     // Snapshot setup.
     let fdir = snapshot::create_output_dir().unwrap();
@@ -53,23 +32,17 @@ fn main() {
 
     // Build the post magnetic/electric functions.
     let post_magnetic = |t: usize, g: &mut Grid| {
-        hy_tfsf_fn(t, g);
+        tfsf.magnetic(t, g);
         abc_fn(t, g);
     };
 
     let post_electric = |t: usize, g: &mut Grid| {
-        ez_tfsf_fn(t, g);
+        tfsf.electric(t, g);
         if t % 10 == 0 {
             snapshot_fn(t / 10, g).unwrap();
         }
     };
 
-    let mut g = Grid::new_1d(SIZE);
-    g.ceze = ceze;
-    g.cezh = cezh;
-    g.chyh = chyh;
-    g.chye = chye;
-
     // Create the FDTDSim.
     let mut fdtd_sim = FDTDSim::new(
         Some(GridDimension::One),
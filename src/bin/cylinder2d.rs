@@ -0,0 +1,63 @@
+// Plane wave scattering from a PEC cylinder in a TM^z grid, after the
+// TFSF programs of chapter 8 of Schneider.
+use fdtd::abc;
+use fdtd::conductor::{Conductor, Conductors};
+use fdtd::fdtd::{FDTDSim, GridDimension, Polarization};
+use fdtd::geometry::Circle;
+use fdtd::grid::Grid;
+use fdtd::snapshot;
+use fdtd::tfsf::Tfsf2d;
+use fdtd::waveform::Ricker;
+
+const SIZE_X: usize = 101;
+const SIZE_Y: usize = 81;
+const RADIUS: f64 = 12.0;
+
+// Must be greater than 0.0;
+const PPW: f64 = 20.0;
+
+fn main() {
+    let mut g = Grid::new_2d(SIZE_X, SIZE_Y, None);
+    let dimension = GridDimension::Two(Polarization::Magnetic);
+
+    // PEC cylinder at the centre of the grid.
+    let mut conductors = Conductors::new(&g);
+    let cylinder = Circle::new([SIZE_X as f64 / 2.0, SIZE_Y as f64 / 2.0], RADIUS);
+    conductors.add(&g, &cylinder, Conductor::Pec);
+
+    // Total-field region five cells in from the edges of the grid.
+    let ricker = Ricker::new(g.cdtds, PPW, 0.0);
+    let tfsf = Tfsf2d::new(&g, (5, 5), (SIZE_X - 6, SIZE_Y - 6), ricker).unwrap();
+    let mut abc_fn = abc::mur_abc_2nd_order(&g, dimension);
+
+    // Snapshot setup.
+    let fdir = snapshot::create_output_dir().unwrap();
+    let snapshot_fn = |t: usize, g: &mut Grid| snapshot::write(g, &fdir, t);
+
+    // Build the post magnetic/electric functions.
+    let post_magnetic = |t: usize, g: &mut Grid| tfsf.magnetic(t, g);
+
+    let post_electric = |t: usize, g: &mut Grid| {
+        tfsf.electric(t, g);
+        abc_fn(t, g);
+        if t % 10 == 0 {
+            snapshot_fn(t / 10, g).unwrap();
+        }
+    };
+
+    // Create the FDTDSim.
+    let mut fdtd_sim = FDTDSim::new(
+        Some(dimension),
+        None,
+        Some(post_magnetic),
+        Some(post_electric),
+        None,
+    )
+    .unwrap();
+
+    fdtd_sim.set_conductors(Some(conductors));
+
+    for _ in 0..300 {
+        fdtd_sim.step(&mut g).unwrap();
+    }
+}
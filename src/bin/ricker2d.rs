@@ -1,9 +1,11 @@
 // src/ricker2d.rs
 // Rust port of 'Program 8.7'.
-use fdtd::fdtd::{FDTDSim, GridDimension, Polarization};
-use fdtd::grid::Grid;
-use fdtd::ricker;
+use fdtd::cpml::{Cpml, CpmlParams};
+use fdtd::fdtd::FDTDSim;
+use fdtd::grid::{Field, Grid, Grid2dTm, GridType};
 use fdtd::snapshot;
+use fdtd::sources::PointSource;
+use fdtd::waveform::Ricker;
 
 const SIZE_X: usize = 101;
 const SIZE_Y: usize = 81;
@@ -18,31 +20,35 @@ fn main() {
     let fdir = snapshot::create_output_dir().unwrap();
     let snapshot_fn = |t: usize, g: &mut Grid| snapshot::write(g, &fdir, t);
 
-    let ez_inc = |t: usize, g: &mut Grid| {
-        let loc = (SIZE_X / 2) * SIZE_Y + (SIZE_Y / 2);
-        g.ez[loc] = ricker::ricker(t as f64, 0.0, cdtds, PPW);
-    };
-
     let post_magnetic = |_t: usize, _g: &mut Grid| {};
 
     let post_electric = |t: usize, g: &mut Grid| {
-        ez_inc(t, g);
         if t % 10 == 0 {
             snapshot_fn(t / 10, g).unwrap();
         }
     };
 
-    let mut g = Grid::new_2d(SIZE_X, SIZE_Y, None);
-
-    // Create the FDTDSim.
-    let mut fdtd_sim = FDTDSim::new(
-        Some(GridDimension::Two(Polarization::Magnetic)),
-        None,
-        Some(post_magnetic),
-        Some(post_electric),
-        None,
-    )
-    .unwrap();
+    let mut g = Grid2dTm::new(SIZE_X, SIZE_Y, None);
+
+    // Create the FDTDSim; its dimension is fixed by the grid type.
+    let mut fdtd_sim =
+        FDTDSim::new(None, None, Some(post_magnetic), Some(post_electric), None).unwrap();
+
+    // Absorb the outgoing wave rather than reflecting off the grid edges.
+    fdtd_sim
+        .set_cpml(Some(Cpml::new(
+            &g,
+            Grid2dTm::DIMENSION.unwrap(),
+            &CpmlParams::default(),
+        )))
+        .unwrap();
+
+    // Hard Ricker wavelet source at the centre of the grid.
+    fdtd_sim.add_source(PointSource::hard(
+        Field::Ez,
+        [SIZE_X / 2, SIZE_Y / 2, 0],
+        Ricker::new(cdtds, PPW, 0.0),
+    ));
 
     for _ in 0..300 {
         fdtd_sim.step(&mut g).unwrap();
@@ -1,7 +1,9 @@
 // src/ricker2d.rs
 // Rust port of 'Program 8.7'.
-use fdtd::fdtd::{FDTDSim, Grid};
-use fdtd::ricker;
+use fdtd::fdtd::FDTDSim;
+use fdtd::grid::{Field, Grid, Grid2dTm};
+use fdtd::sources::PointSource;
+use fdtd::waveform::Ricker;
 
 const SIZE_X: usize = 800;
 const SIZE_Y: usize = 600;
@@ -36,37 +38,18 @@ const PPW: f64 = 20.0;
 fn main() -> Result<(), crow::Error> {
     let cdtds = 1.0 / 2.0f64.sqrt();
 
-    let ez_inc = move |t: usize, g: &mut Grid| {
-        let loc = (SIZE_X / 2) * SIZE_Y + (SIZE_Y / 2);
-        g.ez[loc] = ricker::ricker(t as f64, 0.0, cdtds, PPW);
-    };
+    let mut g = Grid2dTm::new(SIZE_X, SIZE_Y, Some(cdtds));
 
-    let post_magnetic = move |_t: usize, _g: &mut Grid| {};
+    // Create the FDTDSim; its dimension is fixed by the grid type.
+    let mut fdtd_sim: FDTDSim<fn(usize, &mut Grid), fn(usize, &mut Grid), Grid2dTm> =
+        FDTDSim::new(None, None, None, None, None).unwrap();
 
-    let post_electric = move |t: usize, g: &mut Grid| {
-        ez_inc(t, g);
-    };
-
-    let mut fdtd_sim = match FDTDSim::new_2d(
-        SIZE_X,
-        SIZE_Y,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        Some(cdtds),
-    ) {
-        Ok(e) => e,
-        Err(_) => panic!(),
-    };
-
-    fdtd_sim.set_post_magnetic(Some(post_magnetic));
-    fdtd_sim.set_post_electric(Some(post_electric));
+    // Hard Ricker wavelet source at the centre of the grid.
+    fdtd_sim.add_source(PointSource::hard(
+        Field::Ez,
+        [SIZE_X / 2, SIZE_Y / 2, 0],
+        Ricker::new(cdtds, PPW, 0.0),
+    ));
 
     let event_loop = EventLoop::new();
     let mut ctx = Context::new(
@@ -85,7 +68,7 @@ fn main() -> Result<(), crow::Error> {
                     if input.state == ElementState::Pressed
                         && input.virtual_keycode == Some(VirtualKeyCode::Space)
                     {
-                        fdtd_sim.step();
+                        fdtd_sim.step(&mut g).unwrap();
                     }
                 }
                 _ => (),
@@ -95,9 +78,10 @@ fn main() -> Result<(), crow::Error> {
             Event::RedrawRequested(_) => {
                 let mut surface = Scaled::new(ctx.surface(), (1, 1));
                 ctx.clear_color(&mut surface, (0.4, 0.4, 0.8, 1.0));
-                for y in 0..fdtd_sim.g.y_sz {
-                    for x in 0..fdtd_sim.g.x_sz {
-                        let f = fdtd_sim.g.ez[x * fdtd_sim.g.y_sz + y];
+                let ez = g.view(Field::Ez).unwrap();
+                for y in 0..g.y_sz {
+                    for x in 0..g.x_sz {
+                        let f = ez[[x, y, 0]];
                         let color_modulation = mat(float_to_rgb(f));
                         ctx.draw(
                             &mut surface,
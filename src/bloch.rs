@@ -0,0 +1,167 @@
+// src/bloch.rs
+//! Bloch-periodic boundaries through the sine-cosine technique; see chapter
+//! 13 of Taflove and Hagness.
+//!
+//! A mode of a periodic structure with wavevector 'k' satisfies
+//! 'ψ(r + L) = ψ(r) exp(i k·L)', so its fields are complex. The real part is
+//! held by the grid stepped by `FDTDSim`, the imaginary part by a second grid
+//! held by `Bloch`; the two are stepped side by side and only mix across the
+//! periodic faces, where the phase is applied.
+//!
+//! Sweeping 'k' and picking the resonances out of the fields recorded at a
+//! few points gives the band diagram of the structure.
+use crate::error::FDTDError;
+use crate::grid::{Field, Grid};
+use crate::step;
+use std::f64::consts::PI;
+
+/// Bloch-periodic boundaries, along with the imaginary part of the fields.
+#[derive(Debug, Clone)]
+pub struct Bloch {
+    phase: [Option<f64>; 3],
+    imag: Grid,
+}
+
+impl Bloch {
+    /// Bloch-periodic boundaries for the grid 'g', with the phase 'k L' the
+    /// fields pick up across the grid along each axis (x, y, z), in radians;
+    /// 'None' leaves an axis bounded. The imaginary part shares the
+    /// materials of 'g' and starts out zeroed.
+    pub fn new(g: &Grid, phase: [Option<f64>; 3]) -> Self {
+        let mut imag = g.clone();
+        for f in Field::ALL {
+            imag.field_mut(f).0.iter_mut().for_each(|v| *v = 0.0);
+        }
+
+        Bloch { phase, imag }
+    }
+
+    /// Bloch-periodic boundaries for the grid 'g' along the 'periodic' axes,
+    /// with the wavevector 'k' in radians per cell.
+    pub fn with_wavevector(g: &Grid, k: [f64; 3], periodic: [bool; 3]) -> Self {
+        let sz = [g.x_sz, g.y_sz, g.z_sz];
+        let phase = [0, 1, 2].map(|a| periodic[a].then(|| k[a] * sz[a] as f64));
+        Bloch::new(g, phase)
+    }
+
+    pub fn phase(&self) -> [Option<f64>; 3] {
+        self.phase
+    }
+
+    /// Imaginary part of the fields.
+    pub fn imag(&self) -> &Grid {
+        &self.imag
+    }
+
+    pub fn imag_mut(&mut self) -> &mut Grid {
+        &mut self.imag
+    }
+
+    /// Check the imaginary part has the components of 'g', the real part,
+    /// at its size; called before stepping it.
+    pub(crate) fn check(&self, g: &Grid) -> Result<(), FDTDError> {
+        for f in Field::ALL {
+            let (len, expected) = (self.imag.field(f).len(), g.field(f).len());
+            if len != expected {
+                return Err(FDTDError::Shape {
+                    part: "the imaginary part",
+                    field: f,
+                    len,
+                    expected,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Update the magnetic nodes of both parts whose stencil crosses a
+    /// periodic face.
+    pub(crate) fn magnetic(&mut self, g: &mut Grid) {
+        self.update(g, false);
+    }
+
+    /// Update the electric nodes of both parts whose stencil crosses a
+    /// periodic face.
+    pub(crate) fn electric(&mut self, g: &mut Grid) {
+        self.update(g, true);
+    }
+
+    fn update(&mut self, re: &mut Grid, electric: bool) {
+        let periodic = self.phase.map(|p| p.is_some());
+        let phase = self.phase.map(|p| p.unwrap_or(0.0));
+        let (re_part, im): (&Grid, &Grid) = (re, &self.imag);
+
+        // A neighbour across the high face of the grid is the node at the
+        // low face scaled by 'exp(i k L)', and the other way around; 'imag'
+        // picks the part of the product.
+        let value = move |imag: bool| {
+            move |f: Field, p: [usize; 3], axis: usize, d: isize| {
+                let i = re_part.index(p);
+                let (a, b) = (re_part.field(f)[i], im.field(f)[i]);
+                let (s, c) = (d as f64 * phase[axis]).sin_cos();
+                if imag {
+                    a * s + b * c
+                } else {
+                    a * c - b * s
+                }
+            }
+        };
+
+        let updates: Vec<(Field, usize, f64, f64)> =
+            step::wrapped_nodes(re_part, periodic, electric)
+                .into_iter()
+                .map(|(f, pos)| {
+                    (
+                        f,
+                        re_part.index(pos),
+                        step::wrapped_update(re_part, f, pos, value(false)),
+                        step::wrapped_update(im, f, pos, value(true)),
+                    )
+                })
+                .collect();
+
+        for (f, i, a, b) in updates {
+            re.field_mut(f).0[i] = a;
+            self.imag.field_mut(f).0[i] = b;
+        }
+    }
+}
+
+/// Power of the complex time series 're + i im', sampled once per time
+/// step, at 'omega' in radians per time step.
+pub fn spectrum(re: &[f64], im: &[f64], omega: f64) -> f64 {
+    let (sum_re, sum_im) =
+        re.iter()
+            .zip(im)
+            .enumerate()
+            .fold((0.0, 0.0), |(sr, si), (t, (a, b))| {
+                // (a + ib) exp(-iωt)
+                let (s, c) = (omega * t as f64).sin_cos();
+                (sr + a * c + b * s, si + b * c - a * s)
+            });
+
+    sum_re * sum_re + sum_im * sum_im
+}
+
+/// Frequencies among 'omegas', taken in order, at which the spectrum of a
+/// time series peaks above a hundredth of its largest value; these are the
+/// frequencies of the bands at the wavevector the series was recorded at.
+/// The series is tapered by a Hann window first, so that the sidelobes of
+/// one band aren't taken for another.
+pub fn resonances(re: &[f64], im: &[f64], omegas: &[f64]) -> Vec<f64> {
+    let n = re.len().min(im.len());
+    let hann = |t: usize| (PI * t as f64 / (n - 1).max(1) as f64).sin().powi(2);
+    let taper = |v: &[f64]| -> Vec<f64> { (0..n).map(|t| v[t] * hann(t)).collect() };
+    let (re, im) = (taper(re), taper(im));
+
+    let power: Vec<f64> = omegas.iter().map(|w| spectrum(&re, &im, *w)).collect();
+    let largest = power.iter().cloned().fold(0.0, f64::max);
+
+    (1..power.len().saturating_sub(1))
+        .filter(|&i| {
+            power[i] > power[i - 1] && power[i] >= power[i + 1] && power[i] > 1e-2 * largest
+        })
+        .map(|i| omegas[i])
+        .collect()
+}
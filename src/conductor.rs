@@ -0,0 +1,130 @@
+// src/conductor.rs
+//! Perfect electric (PEC) and perfect magnetic (PMC) conductors.
+//!
+//! A conductor zeroes the electric (or magnetic) field within its shape
+//! after each update, which leaves the tangential field on its surface at
+//! zero. Each component is sampled at its own position within the cell, as
+//! with `Grid::paint`.
+use crate::error::FDTDError;
+use crate::geometry::Shape;
+use crate::grid::{Field, Grid};
+use crate::view;
+
+/// Kinds of perfect conductor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conductor {
+    /// Perfect electric conductor; the electric field within it is zero.
+    Pec,
+    /// Perfect magnetic conductor; the magnetic field within it is zero.
+    Pmc,
+}
+
+impl Conductor {
+    fn fields(self) -> [Field; 3] {
+        match self {
+            Conductor::Pec => [Field::Ex, Field::Ey, Field::Ez],
+            Conductor::Pmc => [Field::Hx, Field::Hy, Field::Hz],
+        }
+    }
+}
+
+/// Perfect conductors within a grid, held as a mask per component.
+#[derive(Debug, Clone, Default)]
+pub struct Conductors {
+    // Per component, in the order of 'Field::ALL': 0.0 for nodes within a
+    // conductor and 1.0 elsewhere; empty if the grid lacks the component.
+    masks: [Vec<f64>; 6],
+
+    // Indices of the nodes within a conductor, per component.
+    nodes: [Vec<usize>; 6],
+}
+
+impl Conductors {
+    /// Build an empty set of conductors for grid 'g'.
+    pub fn new(g: &Grid) -> Self {
+        let mut c = Conductors::default();
+        for (k, f) in Field::ALL.into_iter().enumerate() {
+            if g.has(f) {
+                c.masks[k] = vec![1.0; g.len()];
+            }
+        }
+
+        c
+    }
+
+    /// Add a conductor filling 'shape'.
+    pub fn add(&mut self, g: &Grid, shape: &impl Shape, conductor: Conductor) {
+        let fields = conductor.fields();
+
+        for (f, pos) in g.components_in(shape) {
+            if !fields.contains(&f) {
+                continue;
+            }
+
+            let k = slot(f);
+            let i = match view::offset(g.shape(), pos) {
+                Some(i) => i,
+                None => continue,
+            };
+            if self.masks[k][i] != 0.0 {
+                self.masks[k][i] = 0.0;
+                self.nodes[k].push(i);
+            }
+        }
+    }
+
+    /// Whether no node lies within a conductor.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.iter().all(|n| n.is_empty())
+    }
+
+    /// Check the masks cover the components of 'g', which may not be the
+    /// grid the conductors were built for; called before stepping it.
+    pub(crate) fn check(&self, g: &Grid) -> Result<(), FDTDError> {
+        for (k, f) in Field::ALL.into_iter().enumerate() {
+            let (len, expected) = (self.masks[k].len(), g.field(f).len());
+            if len != expected {
+                return Err(FDTDError::Shape {
+                    part: "conductors",
+                    field: f,
+                    len,
+                    expected,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mask of component 'f'; empty if the grid lacks the component.
+    pub(crate) fn mask(&self, f: Field) -> &Vec<f64> {
+        &self.masks[slot(f)]
+    }
+
+    /// Zero the magnetic field within perfect magnetic conductors.
+    pub(crate) fn magnetic(&self, g: &mut Grid) {
+        self.apply(g, &Conductor::Pmc.fields());
+    }
+
+    /// Zero the electric field within perfect electric conductors.
+    pub(crate) fn electric(&self, g: &mut Grid) {
+        self.apply(g, &Conductor::Pec.fields());
+    }
+
+    fn apply(&self, g: &mut Grid, fields: &[Field]) {
+        for &f in fields {
+            let (v, _) = g.field_mut(f);
+            for &i in &self.nodes[slot(f)] {
+                v[i] = 0.0;
+            }
+        }
+    }
+}
+
+// Position of component 'f' within 'Field::ALL'.
+fn slot(f: Field) -> usize {
+    Field::ALL
+        .iter()
+        .position(|a| *a == f)
+        .expect("Unknown field!")
+}
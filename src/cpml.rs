@@ -0,0 +1,532 @@
+// src/cpml.rs
+//! Convolutional perfectly matched layer (CPML); see chapter 11 of
+//! Schneider, and Roden and Gedney, "Convolution PML (CPML): An efficient
+//! FDTD implementation of the CFS-PML for arbitrary media".
+//!
+//! The regular update equations are run over the whole grid; a `Cpml` then
+//! corrects the fields within the layer. Each spatial derivative within the
+//! layer carries an auxiliary 'psi' value which holds the convolution term.
+use crate::fdtd::{GridDimension, Polarization};
+use crate::grid::Grid;
+
+/// Faces of the grid lined by a CPML; faces along axes the grid doesn't
+/// have are ignored.
+#[derive(Debug, Clone, Copy)]
+pub struct CpmlFaces {
+    pub x_lo: bool,
+    pub x_hi: bool,
+    pub y_lo: bool,
+    pub y_hi: bool,
+    pub z_lo: bool,
+    pub z_hi: bool,
+}
+
+impl Default for CpmlFaces {
+    fn default() -> Self {
+        CpmlFaces {
+            x_lo: true,
+            x_hi: true,
+            y_lo: true,
+            y_hi: true,
+            z_lo: true,
+            z_hi: true,
+        }
+    }
+}
+
+/// Parameters of a CPML. Conductivity and alpha are normalized such that
+/// 'sigma' is sigma * IMP0 * dx and 'alpha' is alpha * IMP0 * dx.
+#[derive(Debug, Clone, Copy)]
+pub struct CpmlParams {
+    /// Thickness of the layer in cells.
+    pub thickness: usize,
+    /// Order of the polynomial grading of sigma and kappa.
+    pub order: f64,
+    /// Maximum normalized conductivity; defaults to the optimum of
+    /// 0.8 * (order + 1).
+    pub sigma_max: Option<f64>,
+    /// Maximum kappa, reached at the outer edge of the layer.
+    pub kappa_max: f64,
+    /// Maximum normalized alpha, reached at the inner edge of the layer.
+    pub alpha_max: f64,
+    /// Faces to line with the layer.
+    pub faces: CpmlFaces,
+}
+
+impl Default for CpmlParams {
+    fn default() -> Self {
+        CpmlParams {
+            thickness: 10,
+            order: 3.0,
+            sigma_max: None,
+            kappa_max: 1.0,
+            alpha_max: 0.0,
+            faces: CpmlFaces::default(),
+        }
+    }
+}
+
+/// CPML coefficients along a single axis. Electric nodes sit at integer
+/// positions, magnetic nodes at half-integer positions.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Profile {
+    b_e: Vec<f64>,
+    c_e: Vec<f64>,
+    kappa_e: Vec<f64>,
+    b_h: Vec<f64>,
+    c_h: Vec<f64>,
+    kappa_h: Vec<f64>,
+
+    // Indices which lie within the layer.
+    active_e: Vec<usize>,
+    active_h: Vec<usize>,
+}
+
+impl Profile {
+    /// Build a profile for an axis of 'len' nodes, with a layer against the
+    /// low and/or high face.
+    fn new(len: usize, params: &CpmlParams, cdtds: f64, lo: bool, hi: bool) -> Self {
+        let mut p = Profile {
+            b_e: vec![0.0; len],
+            c_e: vec![0.0; len],
+            kappa_e: vec![1.0; len],
+            b_h: vec![0.0; len],
+            c_h: vec![0.0; len],
+            kappa_h: vec![1.0; len],
+            ..Default::default()
+        };
+
+        if params.thickness == 0 {
+            return p;
+        }
+
+        let thickness = params.thickness as f64;
+        let sigma_max = params.sigma_max.unwrap_or(0.8 * (params.order + 1.0));
+
+        // Normalized depth into the layer of a node at 'pos'; 0.0 when
+        // outside of the layer.
+        let depth = |pos: f64| {
+            let mut d: f64 = 0.0;
+            if lo {
+                d = d.max(thickness - pos);
+            }
+            if hi {
+                d = d.max(pos - (len as f64 - 1.0 - thickness));
+            }
+            (d / thickness).min(1.0)
+        };
+
+        // b, c, and kappa at a given depth.
+        let coef = |d: f64| {
+            let sigma = sigma_max * d.powf(params.order);
+            let kappa = 1.0 + (params.kappa_max - 1.0) * d.powf(params.order);
+            let alpha = params.alpha_max * (1.0 - d);
+
+            let b = (-(sigma / kappa + alpha) * cdtds).exp();
+            let denom = sigma * kappa + kappa * kappa * alpha;
+            let c = if denom == 0.0 {
+                0.0
+            } else {
+                sigma * (b - 1.0) / denom
+            };
+
+            (b, c, kappa)
+        };
+
+        // Electric nodes at 0 and magnetic nodes at len - 1 are never
+        // updated; leave them out.
+        for i in 1..len {
+            let d = depth(i as f64);
+            if d > 0.0 {
+                let (b, c, kappa) = coef(d);
+                p.b_e[i] = b;
+                p.c_e[i] = c;
+                p.kappa_e[i] = kappa;
+                p.active_e.push(i);
+            }
+        }
+
+        for i in 0..len.saturating_sub(1) {
+            let d = depth(i as f64 + 0.5);
+            if d > 0.0 {
+                let (b, c, kappa) = coef(d);
+                p.b_h[i] = b;
+                p.c_h[i] = c;
+                p.kappa_h[i] = kappa;
+                p.active_h.push(i);
+            }
+        }
+
+        p
+    }
+
+    /// Flatten the profile into the rows passed to the Futhark kernels:
+    /// b, c, kappa and whether the node is active, for the electric and then
+    /// the magnetic nodes.
+    pub(crate) fn rows(&self) -> Vec<f64> {
+        let active = |idx: &[usize]| {
+            let mut v = vec![0.0; self.b_e.len()];
+            for &i in idx {
+                v[i] = 1.0;
+            }
+            v
+        };
+
+        [
+            &self.b_e[..],
+            &self.c_e,
+            &self.kappa_e,
+            &active(&self.active_e),
+            &self.b_h,
+            &self.c_h,
+            &self.kappa_h,
+            &active(&self.active_h),
+        ]
+        .concat()
+    }
+
+    /// Advance 'psi' for the electric node 'i' and return the correction to
+    /// apply, relative to the uncorrected spatial difference 'diff'.
+    #[inline]
+    fn e(&self, i: usize, psi: &mut f64, diff: f64) -> f64 {
+        *psi = self.b_e[i] * *psi + self.c_e[i] * diff;
+        (1.0 / self.kappa_e[i] - 1.0) * diff + *psi
+    }
+
+    /// Advance 'psi' for the magnetic node 'i' and return the correction to
+    /// apply, relative to the uncorrected spatial difference 'diff'.
+    #[inline]
+    fn h(&self, i: usize, psi: &mut f64, diff: f64) -> f64 {
+        *psi = self.b_h[i] * *psi + self.c_h[i] * diff;
+        (1.0 / self.kappa_h[i] - 1.0) * diff + *psi
+    }
+}
+
+/// A CPML lining the faces of a grid. Psi arrays are named after the field
+/// they correct and the direction of the derivative; 'psi_hxy' holds the
+/// convolution of uEz/uy within the Hx update.
+#[derive(Debug, Clone)]
+pub struct Cpml {
+    dimension: GridDimension,
+    pub(crate) x: Profile,
+    pub(crate) y: Profile,
+    pub(crate) z: Profile,
+
+    pub(crate) psi_hxy: Vec<f64>,
+    pub(crate) psi_hxz: Vec<f64>,
+    pub(crate) psi_hyx: Vec<f64>,
+    pub(crate) psi_hyz: Vec<f64>,
+    pub(crate) psi_hzx: Vec<f64>,
+    pub(crate) psi_hzy: Vec<f64>,
+
+    pub(crate) psi_exy: Vec<f64>,
+    pub(crate) psi_exz: Vec<f64>,
+    pub(crate) psi_eyx: Vec<f64>,
+    pub(crate) psi_eyz: Vec<f64>,
+    pub(crate) psi_ezx: Vec<f64>,
+    pub(crate) psi_ezy: Vec<f64>,
+}
+
+impl Cpml {
+    /// Build a CPML lining the faces of the grid 'g' selected by
+    /// 'params.faces'.
+    pub fn new(g: &Grid, dimension: GridDimension, params: &CpmlParams) -> Self {
+        let len = match dimension {
+            GridDimension::Two(_) => g.x_sz * g.y_sz,
+            GridDimension::Three => g.x_sz * g.y_sz * g.z_sz,
+            GridDimension::One => panic!("Unimplemented!"),
+        };
+
+        // Only allocate the psi arrays the dimension makes use of.
+        let tm = matches!(
+            dimension,
+            GridDimension::Two(Polarization::Magnetic) | GridDimension::Three
+        );
+        let te = matches!(
+            dimension,
+            GridDimension::Two(Polarization::Electric) | GridDimension::Three
+        );
+        let three = matches!(dimension, GridDimension::Three);
+        let psi = |used: bool| if used { vec![0.0; len] } else { Vec::new() };
+
+        let f = &params.faces;
+        let z = if three {
+            Profile::new(g.z_sz, params, g.cdtds, f.z_lo, f.z_hi)
+        } else {
+            Profile::default()
+        };
+
+        Cpml {
+            dimension,
+            x: Profile::new(g.x_sz, params, g.cdtds, f.x_lo, f.x_hi),
+            y: Profile::new(g.y_sz, params, g.cdtds, f.y_lo, f.y_hi),
+            z,
+
+            psi_hxy: psi(tm),
+            psi_hxz: psi(three),
+            psi_hyx: psi(tm),
+            psi_hyz: psi(three),
+            psi_hzx: psi(te),
+            psi_hzy: psi(te),
+
+            psi_exy: psi(te),
+            psi_exz: psi(three),
+            psi_eyx: psi(te),
+            psi_eyz: psi(three),
+            psi_ezx: psi(tm),
+            psi_ezy: psi(tm),
+        }
+    }
+
+    /// Correct the magnetic field within the layer; called after the
+    /// magnetic update.
+    pub fn magnetic(&mut self, g: &mut Grid) {
+        let y_sz = g.y_sz;
+        let (x, y) = (&self.x, &self.y);
+
+        match self.dimension {
+            GridDimension::Two(Polarization::Magnetic) => {
+                // hx(m, n) -= chxe(m, n) * (ez(m, n + 1) - ez(m, n))
+                for m in 0..g.x_sz {
+                    for &n in &y.active_h {
+                        let i = m * y_sz + n;
+                        let diff = g.ez[i + 1] - g.ez[i];
+                        g.hx[i] -= g.chxe[i] * y.h(n, &mut self.psi_hxy[i], diff);
+                    }
+                }
+
+                // hy(m, n) += chye(m, n) * (ez(m + 1, n) - ez(m, n))
+                for &m in &x.active_h {
+                    for n in 0..y_sz {
+                        let i = m * y_sz + n;
+                        let diff = g.ez[i + y_sz] - g.ez[i];
+                        g.hy[i] += g.chye[i] * x.h(m, &mut self.psi_hyx[i], diff);
+                    }
+                }
+            }
+
+            GridDimension::Two(Polarization::Electric) => {
+                // hz(m, n) += chze(m, n) * (ex(m, n + 1) - ex(m, n))
+                for m in 0..g.x_sz - 1 {
+                    for &n in &y.active_h {
+                        let i = m * y_sz + n;
+                        let diff = g.ex[i + 1] - g.ex[i];
+                        g.hz[i] += g.chze[i] * y.h(n, &mut self.psi_hzy[i], diff);
+                    }
+                }
+
+                // hz(m, n) -= chze(m, n) * (ey(m + 1, n) - ey(m, n))
+                for &m in &x.active_h {
+                    for n in 0..y_sz - 1 {
+                        let i = m * y_sz + n;
+                        let diff = g.ey[i + y_sz] - g.ey[i];
+                        g.hz[i] -= g.chze[i] * x.h(m, &mut self.psi_hzx[i], diff);
+                    }
+                }
+            }
+
+            GridDimension::Three => self.magnetic_3d(g),
+
+            _ => panic!("Unimplemented!"),
+        }
+    }
+
+    /// Correct the electric field within the layer; called after the
+    /// electric update.
+    pub fn electric(&mut self, g: &mut Grid) {
+        let y_sz = g.y_sz;
+        let (x, y) = (&self.x, &self.y);
+
+        match self.dimension {
+            GridDimension::Two(Polarization::Magnetic) => {
+                // ez(m, n) += cezh(m, n) * (hy(m, n) - hy(m - 1, n))
+                for &m in &x.active_e {
+                    for n in 1..y_sz {
+                        let i = m * y_sz + n;
+                        let diff = g.hy[i] - g.hy[i - y_sz];
+                        g.ez[i] += g.cezh[i] * x.e(m, &mut self.psi_ezx[i], diff);
+                    }
+                }
+
+                // ez(m, n) -= cezh(m, n) * (hx(m, n) - hx(m, n - 1))
+                for m in 1..g.x_sz {
+                    for &n in &y.active_e {
+                        let i = m * y_sz + n;
+                        let diff = g.hx[i] - g.hx[i - 1];
+                        g.ez[i] -= g.cezh[i] * y.e(n, &mut self.psi_ezy[i], diff);
+                    }
+                }
+            }
+
+            GridDimension::Two(Polarization::Electric) => {
+                // ex(m, n) += cexh(m, n) * (hz(m, n) - hz(m, n - 1))
+                for m in 0..g.x_sz {
+                    for &n in &y.active_e {
+                        let i = m * y_sz + n;
+                        let diff = g.hz[i] - g.hz[i - 1];
+                        g.ex[i] += g.cexh[i] * y.e(n, &mut self.psi_exy[i], diff);
+                    }
+                }
+
+                // ey(m, n) -= ceyh(m, n) * (hz(m, n) - hz(m - 1, n))
+                for &m in &x.active_e {
+                    for n in 0..y_sz {
+                        let i = m * y_sz + n;
+                        let diff = g.hz[i] - g.hz[i - y_sz];
+                        g.ey[i] -= g.ceyh[i] * x.e(m, &mut self.psi_eyx[i], diff);
+                    }
+                }
+            }
+
+            GridDimension::Three => self.electric_3d(g),
+
+            _ => panic!("Unimplemented!"),
+        }
+    }
+
+    // The order in which the corrections are applied to a node must match
+    // the Futhark kernels, so both backends produce identical results.
+    fn magnetic_3d(&mut self, g: &mut Grid) {
+        let (x_sz, y_sz, z_sz) = (g.x_sz, g.y_sz, g.z_sz);
+        let (x, y, z) = (&self.x, &self.y, &self.z);
+        let idx = |m: usize, n: usize, p: usize| (m * y_sz + n) * z_sz + p;
+
+        // hx(m, n, p) -= chxe(m, n, p) * (ez(m, n + 1, p) - ez(m, n, p))
+        for m in 0..x_sz {
+            for &n in &y.active_h {
+                for p in 0..z_sz - 1 {
+                    let i = idx(m, n, p);
+                    let diff = g.ez[i + z_sz] - g.ez[i];
+                    g.hx[i] -= g.chxe[i] * y.h(n, &mut self.psi_hxy[i], diff);
+                }
+            }
+        }
+
+        // hx(m, n, p) += chxe(m, n, p) * (ey(m, n, p + 1) - ey(m, n, p))
+        for m in 0..x_sz {
+            for n in 0..y_sz - 1 {
+                for &p in &z.active_h {
+                    let i = idx(m, n, p);
+                    let diff = g.ey[i + 1] - g.ey[i];
+                    g.hx[i] += g.chxe[i] * z.h(p, &mut self.psi_hxz[i], diff);
+                }
+            }
+        }
+
+        // hy(m, n, p) += chye(m, n, p) * (ez(m + 1, n, p) - ez(m, n, p))
+        for &m in &x.active_h {
+            for n in 0..y_sz {
+                for p in 0..z_sz - 1 {
+                    let i = idx(m, n, p);
+                    let diff = g.ez[i + y_sz * z_sz] - g.ez[i];
+                    g.hy[i] += g.chye[i] * x.h(m, &mut self.psi_hyx[i], diff);
+                }
+            }
+        }
+
+        // hy(m, n, p) -= chye(m, n, p) * (ex(m, n, p + 1) - ex(m, n, p))
+        for m in 0..x_sz - 1 {
+            for n in 0..y_sz {
+                for &p in &z.active_h {
+                    let i = idx(m, n, p);
+                    let diff = g.ex[i + 1] - g.ex[i];
+                    g.hy[i] -= g.chye[i] * z.h(p, &mut self.psi_hyz[i], diff);
+                }
+            }
+        }
+
+        // hz(m, n, p) -= chze(m, n, p) * (ey(m + 1, n, p) - ey(m, n, p))
+        for &m in &x.active_h {
+            for n in 0..y_sz - 1 {
+                for p in 0..z_sz {
+                    let i = idx(m, n, p);
+                    let diff = g.ey[i + y_sz * z_sz] - g.ey[i];
+                    g.hz[i] -= g.chze[i] * x.h(m, &mut self.psi_hzx[i], diff);
+                }
+            }
+        }
+
+        // hz(m, n, p) += chze(m, n, p) * (ex(m, n + 1, p) - ex(m, n, p))
+        for m in 0..x_sz - 1 {
+            for &n in &y.active_h {
+                for p in 0..z_sz {
+                    let i = idx(m, n, p);
+                    let diff = g.ex[i + z_sz] - g.ex[i];
+                    g.hz[i] += g.chze[i] * y.h(n, &mut self.psi_hzy[i], diff);
+                }
+            }
+        }
+    }
+
+    fn electric_3d(&mut self, g: &mut Grid) {
+        let (x_sz, y_sz, z_sz) = (g.x_sz, g.y_sz, g.z_sz);
+        let (x, y, z) = (&self.x, &self.y, &self.z);
+        let idx = |m: usize, n: usize, p: usize| (m * y_sz + n) * z_sz + p;
+
+        // ex(m, n, p) += cexh(m, n, p) * (hz(m, n, p) - hz(m, n - 1, p))
+        for m in 0..x_sz {
+            for &n in &y.active_e {
+                for p in 1..z_sz {
+                    let i = idx(m, n, p);
+                    let diff = g.hz[i] - g.hz[i - z_sz];
+                    g.ex[i] += g.cexh[i] * y.e(n, &mut self.psi_exy[i], diff);
+                }
+            }
+        }
+
+        // ex(m, n, p) -= cexh(m, n, p) * (hy(m, n, p) - hy(m, n, p - 1))
+        for m in 0..x_sz {
+            for n in 1..y_sz {
+                for &p in &z.active_e {
+                    let i = idx(m, n, p);
+                    let diff = g.hy[i] - g.hy[i - 1];
+                    g.ex[i] -= g.cexh[i] * z.e(p, &mut self.psi_exz[i], diff);
+                }
+            }
+        }
+
+        // ey(m, n, p) -= ceyh(m, n, p) * (hz(m, n, p) - hz(m - 1, n, p))
+        for &m in &x.active_e {
+            for n in 0..y_sz {
+                for p in 1..z_sz {
+                    let i = idx(m, n, p);
+                    let diff = g.hz[i] - g.hz[i - y_sz * z_sz];
+                    g.ey[i] -= g.ceyh[i] * x.e(m, &mut self.psi_eyx[i], diff);
+                }
+            }
+        }
+
+        // ey(m, n, p) += ceyh(m, n, p) * (hx(m, n, p) - hx(m, n, p - 1))
+        for m in 1..x_sz {
+            for n in 0..y_sz {
+                for &p in &z.active_e {
+                    let i = idx(m, n, p);
+                    let diff = g.hx[i] - g.hx[i - 1];
+                    g.ey[i] += g.ceyh[i] * z.e(p, &mut self.psi_eyz[i], diff);
+                }
+            }
+        }
+
+        // ez(m, n, p) += cezh(m, n, p) * (hy(m, n, p) - hy(m - 1, n, p))
+        for &m in &x.active_e {
+            for n in 1..y_sz {
+                for p in 0..z_sz {
+                    let i = idx(m, n, p);
+                    let diff = g.hy[i] - g.hy[i - y_sz * z_sz];
+                    g.ez[i] += g.cezh[i] * x.e(m, &mut self.psi_ezx[i], diff);
+                }
+            }
+        }
+
+        // ez(m, n, p) -= cezh(m, n, p) * (hx(m, n, p) - hx(m, n - 1, p))
+        for m in 1..x_sz {
+            for &n in &y.active_e {
+                for p in 0..z_sz {
+                    let i = idx(m, n, p);
+                    let diff = g.hx[i] - g.hx[i - z_sz];
+                    g.ez[i] -= g.cezh[i] * y.e(n, &mut self.psi_ezy[i], diff);
+                }
+            }
+        }
+    }
+}
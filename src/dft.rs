@@ -0,0 +1,41 @@
+// src/dft.rs
+//! Running discrete Fourier transforms of the fields, shared by monitors
+//! and the NTFF. Fields are transformed with the kernel 'exp(-iωt)', with 'ω'
+//! in radians per time step; each component is taken at the time it's
+//! sampled at, half a step earlier for the magnetic field.
+use crate::grid::Field;
+
+/// A complex number as (real, imaginary) parts.
+pub type Complex = (f64, f64);
+
+/// The DFT kernel 'exp(-iωt)'.
+pub(crate) fn kernel(omega: f64, t: f64) -> Complex {
+    let (sin, cos) = (omega * t).sin_cos();
+    (cos, -sin)
+}
+
+/// Time component 'f' is sampled at once 'time' steps have been taken.
+pub(crate) fn sample_time(f: Field, time: usize) -> f64 {
+    if f.is_electric() {
+        time as f64
+    } else {
+        time as f64 - 0.5
+    }
+}
+
+pub(crate) fn add(a: Complex, b: Complex) -> Complex {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+pub(crate) fn mul(a: Complex, b: Complex) -> Complex {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+pub(crate) fn scale(a: Complex, s: f64) -> Complex {
+    (a.0 * s, a.1 * s)
+}
+
+/// Squared magnitude.
+pub(crate) fn norm(a: Complex) -> f64 {
+    a.0 * a.0 + a.1 * a.1
+}
@@ -0,0 +1,289 @@
+// src/dispersion.rs
+//! Frequency-dispersive media through auxiliary differential equations
+//! (ADE); see chapter 9 of Taflove and Hagness.
+//!
+//! Each pole of a medium adds a polarization 'P' to the electric flux,
+//! 'D = ε0 ε∞ E + Σ P', and so a polarization current 'J = ∂P/∂t' to the
+//! electric update. The regular update equations are run over the whole
+//! grid with the coefficients of the instantaneous part of the medium; a
+//! `Dispersion` then corrects the electric nodes within the medium.
+//!
+//! The grid has no physical time step, so pole parameters are given in
+//! units of it: frequencies in radians per time step and relaxation times in
+//! time steps.
+use crate::error::FDTDError;
+use crate::geometry::Shape;
+use crate::grid::{Field, Grid, IMP0};
+use crate::material::Material;
+
+/// A pole of the susceptibility of a medium.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pole {
+    /// 'χ(ω) = delta_eps / (1 + iωτ)'.
+    Debye { delta_eps: f64, tau: f64 },
+    /// 'χ(ω) = -omega_p² / (ω² - iωγ)'.
+    Drude { omega_p: f64, gamma: f64 },
+    /// 'χ(ω) = delta_eps ω0² / (ω0² - ω² + iωγ)'.
+    Lorentz {
+        delta_eps: f64,
+        omega_0: f64,
+        gamma: f64,
+    },
+}
+
+impl Pole {
+    /// Susceptibility of the pole at 'omega', in radians per time step, as
+    /// (real, imaginary) parts with an 'exp(iωt)' time dependence.
+    pub fn susceptibility(&self, omega: f64) -> (f64, f64) {
+        // Divide 'n' by the complex number (re, im).
+        let div = |n: f64, re: f64, im: f64| {
+            let d = re * re + im * im;
+            (n * re / d, -n * im / d)
+        };
+
+        match *self {
+            Pole::Debye { delta_eps, tau } => div(delta_eps, 1.0, omega * tau),
+            Pole::Drude { omega_p, gamma } => {
+                div(-omega_p * omega_p, omega * omega, -omega * gamma)
+            }
+            Pole::Lorentz {
+                delta_eps,
+                omega_0,
+                gamma,
+            } => div(
+                delta_eps * omega_0 * omega_0,
+                omega_0 * omega_0 - omega * omega,
+                omega * gamma,
+            ),
+        }
+    }
+
+    /// Coefficients of the polarization update. With the polarization
+    /// normalized by ε0,
+    /// 'P⁺ = c[0] P + c[1] P⁻ + c[2] E⁺ + c[3] E + c[4] E⁻'.
+    fn coefficients(&self) -> [f64; 5] {
+        match *self {
+            // τ ∂P/∂t + P = Δε E, with trapezoidal averages of P and E.
+            Pole::Debye { delta_eps, tau } => {
+                let d = tau + 0.5;
+                [
+                    (tau - 0.5) / d,
+                    0.0,
+                    0.5 * delta_eps / d,
+                    0.5 * delta_eps / d,
+                    0.0,
+                ]
+            }
+
+            // ∂²P/∂t² + γ ∂P/∂t + ω0² P = s E, with central differences
+            // about the current time step and E averaged over the next and
+            // previous ones, which keeps the update stable up to the
+            // Courant limit.
+            Pole::Drude { omega_p, gamma } => Pole::second_order(0.0, gamma, omega_p * omega_p),
+            Pole::Lorentz {
+                delta_eps,
+                omega_0,
+                gamma,
+            } => Pole::second_order(omega_0, gamma, delta_eps * omega_0 * omega_0),
+        }
+    }
+
+    fn second_order(omega_0: f64, gamma: f64, s: f64) -> [f64; 5] {
+        let d = 1.0 + 0.5 * gamma;
+        [
+            (2.0 - omega_0 * omega_0) / d,
+            -(1.0 - 0.5 * gamma) / d,
+            0.5 * s / d,
+            0.0,
+            0.5 * s / d,
+        ]
+    }
+}
+
+/// A dispersive medium: an instantaneous material, whose permittivity is
+/// 'ε∞', plus any number of poles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DispersiveMaterial {
+    pub material: Material,
+    pub poles: Vec<Pole>,
+}
+
+impl DispersiveMaterial {
+    pub fn new(material: Material, poles: Vec<Pole>) -> Self {
+        DispersiveMaterial { material, poles }
+    }
+
+    /// Relative permittivity at 'omega', in radians per time step, as (real,
+    /// imaginary) parts; conductivity is left out.
+    pub fn permittivity(&self, omega: f64) -> (f64, f64) {
+        self.poles
+            .iter()
+            .map(|p| p.susceptibility(omega))
+            .fold((self.material.eps_r, 0.0), |(re, im), (r, i)| {
+                (re + r, im + i)
+            })
+    }
+}
+
+/// Nodes of a single component within a dispersive medium.
+#[derive(Debug, Clone)]
+struct Medium {
+    field: Field,
+    poles: Vec<[f64; 5]>,
+
+    // Index of each node, and its electric field before the update and one
+    // step earlier.
+    nodes: Vec<usize>,
+    e: Vec<f64>,
+    e_old: Vec<f64>,
+
+    // Current and previous polarization of each node, pole by pole.
+    p: Vec<Vec<f64>>,
+    p_old: Vec<Vec<f64>>,
+}
+
+/// Dispersive media painted into a grid, along with their polarizations.
+#[derive(Debug, Clone, Default)]
+pub struct Dispersion {
+    media: Vec<Medium>,
+}
+
+impl Dispersion {
+    pub fn new() -> Self {
+        Dispersion::default()
+    }
+
+    /// Paint a dispersive medium into the grid wherever 'shape' contains an
+    /// electric component: the instantaneous material is painted as by
+    /// `Grid::paint`, and the electric nodes are given the poles. Nodes
+    /// already within a dispersive medium are taken over by the new one.
+    pub fn paint(&mut self, g: &mut Grid, shape: &impl Shape, m: &DispersiveMaterial) {
+        g.paint(shape, &m.material);
+
+        let used = [true, g.y_sz > 0, g.z_sz > 0];
+        let components = g.components_in(shape);
+        let poles: Vec<[f64; 5]> = m.poles.iter().map(|p| p.coefficients()).collect();
+
+        for f in [Field::Ex, Field::Ey, Field::Ez] {
+            // Nodes on the faces of the grid the component is tangential to
+            // are never updated.
+            let nodes: Vec<usize> = components
+                .iter()
+                .filter(|(c, pos)| {
+                    *c == f && (0..3).all(|a| a == f.axis() || !used[a] || pos[a] > 0)
+                })
+                .map(|(_, pos)| g.index(*pos))
+                .collect();
+
+            if nodes.is_empty() {
+                continue;
+            }
+
+            for medium in self.media.iter_mut().filter(|m| m.field == f) {
+                medium.retain(|i| nodes.binary_search(&i).is_err());
+            }
+
+            if !poles.is_empty() {
+                let len = nodes.len();
+                self.media.push(Medium {
+                    field: f,
+                    poles: poles.clone(),
+                    nodes,
+                    e: vec![0.0; len],
+                    e_old: vec![0.0; len],
+                    p: vec![vec![0.0; len]; poles.len()],
+                    p_old: vec![vec![0.0; len]; poles.len()],
+                });
+            }
+        }
+
+        self.media.retain(|m| !m.nodes.is_empty());
+    }
+
+    /// Check every dispersive node lies within 'g', which may not be the
+    /// grid the media were painted into; called before stepping it.
+    pub(crate) fn check(&self, g: &Grid) -> Result<(), FDTDError> {
+        for m in &self.media {
+            let len = g.field(m.field).len();
+            if let Some(&i) = m.nodes.iter().find(|i| **i >= len) {
+                return Err(FDTDError::Node {
+                    field: m.field,
+                    pos: g.position(i),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Record the electric field before the electric update, keeping the
+    /// one recorded at the previous step.
+    pub(crate) fn pre_electric(&mut self, g: &mut Grid) {
+        for m in &mut self.media {
+            let (f, _) = g.field_mut(m.field);
+            for (k, &i) in m.nodes.iter().enumerate() {
+                m.e_old[k] = m.e[k];
+                m.e[k] = f[i];
+            }
+        }
+    }
+
+    /// Advance the polarizations and correct the electric field after the
+    /// electric update.
+    pub(crate) fn electric(&mut self, g: &mut Grid) {
+        let cdtds = g.cdtds;
+
+        for m in &mut self.media {
+            let (f, coef) = g.field_mut(m.field);
+
+            for (k, &i) in m.nodes.iter().enumerate() {
+                // The polarization current enters the update as
+                // 'E⁺ = E* - c ΔP', where 'E*' is the regular update; 'c' is
+                // 1 / ε∞ scaled by any conductive loss.
+                let c = coef[i] / (cdtds * IMP0);
+
+                // Each pole gives 'P⁺ = a + b E⁺'; solve for 'E⁺'.
+                let (e, e_old) = (m.e[k], m.e_old[k]);
+                let (mut sum_a, mut sum_b) = (0.0, 0.0);
+                for (q, pole) in m.poles.iter().enumerate() {
+                    sum_a += explicit(pole, m.p[q][k], m.p_old[q][k], e, e_old) - m.p[q][k];
+                    sum_b += pole[2];
+                }
+
+                let e_new = (f[i] - c * sum_a) / (1.0 + c * sum_b);
+                f[i] = e_new;
+
+                for (q, pole) in m.poles.iter().enumerate() {
+                    let p = explicit(pole, m.p[q][k], m.p_old[q][k], e, e_old) + pole[2] * e_new;
+                    m.p_old[q][k] = m.p[q][k];
+                    m.p[q][k] = p;
+                }
+            }
+        }
+    }
+}
+
+/// The part of a pole's polarization update which doesn't depend on 'E⁺'.
+#[inline]
+fn explicit(pole: &[f64; 5], p: f64, p_old: f64, e: f64, e_old: f64) -> f64 {
+    pole[0] * p + pole[1] * p_old + pole[3] * e + pole[4] * e_old
+}
+
+impl Medium {
+    /// Keep only the nodes whose index satisfies 'f'.
+    fn retain(&mut self, f: impl Fn(usize) -> bool) {
+        let keep: Vec<bool> = self.nodes.iter().map(|&i| f(i)).collect();
+        let filter = |v: &mut Vec<f64>| {
+            let mut k = keep.iter();
+            v.retain(|_| *k.next().unwrap());
+        };
+
+        filter(&mut self.e);
+        filter(&mut self.e_old);
+        self.p.iter_mut().for_each(&filter);
+        self.p_old.iter_mut().for_each(&filter);
+
+        let mut k = keep.iter();
+        self.nodes.retain(|_| *k.next().unwrap());
+    }
+}
@@ -0,0 +1,319 @@
+// src/energy.rs
+//! Electromagnetic energy diagnostics: the energy stored in the fields of a
+//! region, and a monitor which balances it against the energy absorbed by
+//! lossy materials and the energy flowing out through the region's faces,
+//! into boundaries such as the CPML.
+//!
+//! The grid has no physical cell size, so energies are given in units of
+//! 'ε0Δx^d' on a 'd'-dimensional grid: a node holds '(εr E² + μr η0² H²)/2'.
+//! The permittivity, permeability and losses of each node are recovered
+//! from its update coefficients; the energy held by dispersive media is not
+//! accounted for.
+use crate::grid::{Field, Grid, IMP0};
+use crate::monitor::Region;
+use crate::observer::Observer;
+
+/// Energy stored in the electric and magnetic fields.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Energy {
+    pub electric: f64,
+    pub magnetic: f64,
+}
+
+impl Energy {
+    pub fn total(&self) -> f64 {
+        self.electric + self.magnetic
+    }
+}
+
+/// A component over the nodes of a region, with the weight of 'v²/2' in the
+/// energy of each node, 'εr' or 'μr η0²', and the energy absorbed over a
+/// step per unit of its mean value squared.
+#[derive(Debug, Clone)]
+struct Component {
+    f: Field,
+    indices: Vec<usize>,
+    weights: Vec<f64>,
+    losses: Vec<f64>,
+}
+
+impl Component {
+    fn new(g: &Grid, f: Field, nodes: &[[usize; 3]]) -> Self {
+        let (c_own, c_curl) = g.coefficients(f);
+        let indices: Vec<usize> = nodes.iter().map(|p| g.index(*p)).collect();
+
+        // From the coefficients of 'Material', the loss per step is
+        // '(1 - own) / (1 + own)', and the curl coefficient is
+        // 'cdtds η0 / εr / (1 + loss)' for the electric field or
+        // 'cdtds / η0 / μr / (1 + loss)' for the magnetic field. Frozen
+        // nodes hold no energy.
+        let (weights, losses) = indices
+            .iter()
+            .map(|&i| {
+                if c_curl[i] == 0.0 {
+                    return (0.0, 0.0);
+                }
+
+                let loss = (1.0 - c_own[i]) / (1.0 + c_own[i]);
+                let scale = if f.is_electric() { IMP0 } else { 1.0 / IMP0 };
+                let relative = g.cdtds * scale / c_curl[i] / (1.0 + loss);
+                let weight = if f.is_electric() {
+                    relative
+                } else {
+                    relative * IMP0 * IMP0
+                };
+                (weight, 2.0 * weight * loss)
+            })
+            .unzip();
+
+        Component {
+            f,
+            indices,
+            weights,
+            losses,
+        }
+    }
+
+    fn values(&self, g: &Grid) -> Vec<f64> {
+        let v = g.field(self.f);
+        self.indices.iter().map(|&i| v[i]).collect()
+    }
+}
+
+fn components(g: &Grid, region: Region) -> Vec<Component> {
+    let nodes = region.nodes(g);
+    Field::ALL
+        .iter()
+        .filter(|f| g.has(**f))
+        .map(|f| Component::new(g, *f, &nodes))
+        .collect()
+}
+
+/// Energy stored in 'region' of 'g', from the electric field and the
+/// magnetic field half a step before it.
+pub fn stored(g: &Grid, region: Region) -> Energy {
+    let mut energy = Energy::default();
+    for c in components(g, region) {
+        let sum: f64 = c
+            .values(g)
+            .iter()
+            .zip(&c.weights)
+            .map(|(v, w)| 0.5 * w * v * v)
+            .sum();
+        if c.f.is_electric() {
+            energy.electric += sum;
+        } else {
+            energy.magnetic += sum;
+        }
+    }
+    energy
+}
+
+/// A term 'sign E_e H_h' of the outward flux through a face of a region:
+/// the electric field on the face, and the magnetic field half a cell
+/// within the region for the high face or without it for the low face,
+/// with the index of either, node by node.
+#[derive(Debug, Clone)]
+struct FaceTerm {
+    e: Field,
+    h: Field,
+    sign: f64,
+    pairs: Vec<(usize, usize)>,
+
+    // Values of either component at the last step.
+    previous: Option<(Vec<f64>, Vec<f64>)>,
+}
+
+impl FaceTerm {
+    /// Terms of the face at 'index' along 'axis' of 'region', with 'sign'
+    /// its outward direction.
+    fn face(g: &Grid, region: Region, axis: usize, index: usize, sign: f64) -> Vec<Self> {
+        let mut face = region;
+        face.min[axis] = index;
+        face.max[axis] = index + 1;
+        let pairs: Vec<(usize, usize)> = face
+            .nodes(g)
+            .iter()
+            .map(|p| {
+                let mut q = *p;
+                q[axis] -= 1;
+                (g.index(*p), g.index(q))
+            })
+            .collect();
+
+        let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+        [(b, c, sign), (c, b, -sign)]
+            .into_iter()
+            .map(|(e, h, sign)| (Field::electric(e), Field::magnetic(h), sign))
+            .filter(|(e, h, _)| g.has(*e) && g.has(*h))
+            .map(|(e, h, sign)| FaceTerm {
+                e,
+                h,
+                sign,
+                pairs: pairs.clone(),
+                previous: None,
+            })
+            .collect()
+    }
+
+    /// Flux over the last step, with the electric field half way through
+    /// the step and the magnetic field averaged over it.
+    fn observe(&mut self, g: &Grid) -> Option<f64> {
+        let (e, h) = (g.field(self.e), g.field(self.h));
+        let (e, h): (Vec<f64>, Vec<f64>) = self.pairs.iter().map(|&(i, j)| (e[i], h[j])).unzip();
+
+        let flux = self.previous.as_ref().map(|(pe, ph)| {
+            let sum: f64 = (0..h.len()).map(|n| pe[n] * 0.5 * (ph[n] + h[n])).sum();
+            self.sign * sum
+        });
+
+        self.previous = Some((e, h));
+        flux
+    }
+}
+
+/// Energy of a region over time, with a value per step from time 'start()'
+/// on, along with the energy absorbed within it and flowing out of it so
+/// far. Without sources, their sum stays constant.
+///
+/// Values are taken half a step before the electric field, when the
+/// magnetic field is sampled. The electric energy is found from the product
+/// of the electric field either side, and losses and flux from the fields
+/// in between, so that the balance holds exactly for the leapfrog updates.
+#[derive(Debug, Clone)]
+pub struct EnergyMonitor {
+    components: Vec<Component>,
+    faces: Vec<FaceTerm>,
+
+    // Values of each component at the last two steps.
+    previous: Option<Vec<Vec<f64>>>,
+    before: Option<Vec<Vec<f64>>>,
+
+    start: f64,
+    stored: Vec<Energy>,
+    absorbed: Vec<f64>,
+    outflow: Vec<f64>,
+}
+
+impl EnergyMonitor {
+    /// Monitor the energy in 'region' of 'g'. Faces of the region on the
+    /// edges of the grid are taken to let nothing through.
+    pub fn new(g: &Grid, region: Region) -> Self {
+        let sz = [g.x_sz, g.y_sz, g.z_sz];
+        let mut region = region;
+        for (m, s) in region.max.iter_mut().zip(sz) {
+            *m = (*m).min(s);
+        }
+
+        let mut faces = Vec::new();
+        for axis in (0..3).filter(|a| sz[*a] > 0) {
+            for (index, sign) in [(region.min[axis], -1.0), (region.max[axis], 1.0)] {
+                if index > 0 && index < sz[axis] {
+                    faces.extend(FaceTerm::face(g, region, axis, index, sign));
+                }
+            }
+        }
+
+        EnergyMonitor {
+            components: components(g, region),
+            faces,
+            previous: None,
+            before: None,
+            start: 0.0,
+            stored: Vec::new(),
+            absorbed: Vec::new(),
+            outflow: Vec::new(),
+        }
+    }
+
+    /// Time of the first values.
+    pub fn start(&self) -> f64 {
+        self.start
+    }
+
+    /// Energy stored at each step.
+    pub fn stored(&self) -> &[Energy] {
+        &self.stored
+    }
+
+    /// Energy absorbed by lossy materials so far, at each step.
+    pub fn absorbed(&self) -> &[f64] {
+        &self.absorbed
+    }
+
+    /// Energy that has flowed out through the faces so far, at each step.
+    pub fn outflow(&self) -> &[f64] {
+        &self.outflow
+    }
+
+    /// Energy stored, absorbed and flowed out, at each step.
+    pub fn balance(&self) -> Vec<f64> {
+        self.stored
+            .iter()
+            .zip(&self.absorbed)
+            .zip(&self.outflow)
+            .map(|((s, a), o)| s.total() + a + o)
+            .collect()
+    }
+}
+
+impl Observer for EnergyMonitor {
+    fn observe(&mut self, time: usize, g: &Grid) {
+        let values: Vec<Vec<f64>> = self.components.iter().map(|c| c.values(g)).collect();
+
+        // Power through a unit area over a step is 'SΔt', or 'S cdtds η0'
+        // in units of 'ε0Δx^d'.
+        let flux: f64 = self
+            .faces
+            .iter_mut()
+            .filter_map(|f| f.observe(g))
+            .sum::<f64>()
+            * g.cdtds
+            * IMP0;
+
+        if let Some(previous) = &self.previous {
+            let mut energy = Energy::default();
+            for ((c, p), v) in self.components.iter().zip(previous).zip(&values) {
+                for n in 0..v.len() {
+                    if c.f.is_electric() {
+                        energy.electric += 0.5 * c.weights[n] * p[n] * v[n];
+                    } else {
+                        energy.magnetic += 0.5 * c.weights[n] * v[n] * v[n];
+                    }
+                }
+            }
+
+            // Losses since the last values, over the electric field at the
+            // middle of the step and the magnetic field averaged over it.
+            let mut absorbed = 0.0;
+            if let Some(before) = &self.before {
+                absorbed = *self.absorbed.last().unwrap();
+                let fields = self.components.iter().zip(before.iter().zip(previous));
+                for ((c, (b, p)), v) in fields.zip(&values) {
+                    for n in 0..v.len() {
+                        absorbed += if c.f.is_electric() {
+                            0.25 * c.losses[n] * p[n] * (b[n] + 2.0 * p[n] + v[n])
+                        } else {
+                            0.25 * c.losses[n] * (p[n] + v[n]).powi(2)
+                        };
+                    }
+                }
+            }
+
+            let outflow = match self.outflow.last() {
+                Some(o) => o + flux,
+                None => {
+                    self.start = time as f64 - 0.5;
+                    0.0
+                }
+            };
+
+            self.stored.push(energy);
+            self.absorbed.push(absorbed);
+            self.outflow.push(outflow);
+        }
+
+        self.before = self.previous.take();
+        self.previous = Some(values);
+    }
+}
@@ -1,4 +1,6 @@
 // src/error.rs
+use crate::fdtd::GridDimension;
+use crate::grid::{Axis, Field};
 use fdtd_futhark::Error;
 use std::error;
 use std::fmt;
@@ -6,6 +8,78 @@ use std::fmt;
 #[derive(Debug)]
 pub enum FDTDError {
     FutharkError(Error),
+    /// The Courant number is beyond the stability limit of the grid's
+    /// dimension.
+    Courant {
+        cdtds: f64,
+        limit: f64,
+    },
+    /// The grid doesn't have the axes or components a dimension updates.
+    Dimension {
+        dimension: GridDimension,
+        sizes: [usize; 3],
+    },
+    /// A simulation was asked to step a grid type of another dimension.
+    GridType {
+        dimension: GridDimension,
+        expected: GridDimension,
+    },
+    /// A field component or its coefficients don't cover the grid.
+    Length {
+        field: Field,
+        len: usize,
+        expected: usize,
+    },
+    /// An update coefficient would make the update unstable.
+    Coefficient {
+        field: Field,
+        pos: [usize; 3],
+        reason: &'static str,
+    },
+    /// Two features of a simulation can't be combined.
+    Combination {
+        first: &'static str,
+        second: &'static str,
+    },
+    /// A region between 'min' and 'max' doesn't suit the grid or what it's
+    /// used for.
+    Region {
+        min: [usize; 3],
+        max: [usize; 3],
+        reason: &'static str,
+    },
+    /// Part of a simulation was built for a grid of another shape: it holds
+    /// 'len' nodes of a component where the grid has 'expected'.
+    Shape {
+        part: &'static str,
+        field: Field,
+        len: usize,
+        expected: usize,
+    },
+    /// A plane wave was polarized along its direction of propagation,
+    /// 'axis'.
+    Polarization {
+        axis: Axis,
+    },
+    /// The grid has no node of a component at a position, either as it
+    /// lies beyond the grid or the grid doesn't have the component.
+    Node {
+        field: Field,
+        pos: [usize; 3],
+    },
+    /// Something was asked to act every 'interval' steps, which must be at
+    /// least one.
+    Interval {
+        interval: usize,
+    },
+    /// A field turned unstable: a value which isn't finite, or a peak which
+    /// has grown exponentially, once 'step' steps had been taken.
+    Unstable {
+        step: usize,
+        field: Field,
+        pos: [usize; 3],
+        value: f64,
+    },
 }
 
 impl From<Error> for FDTDError {
@@ -18,6 +92,73 @@ impl fmt::Display for FDTDError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             FDTDError::FutharkError(e) => write!(f, "FutharkError: {}", e),
+            FDTDError::Courant { cdtds, limit } => write!(
+                f,
+                "Courant number {} exceeds the stability limit of {}",
+                cdtds, limit
+            ),
+            FDTDError::Dimension { dimension, sizes } => write!(
+                f,
+                "Grid of size {:?} can't be stepped as {:?}",
+                sizes, dimension
+            ),
+            FDTDError::GridType {
+                dimension,
+                expected,
+            } => write!(
+                f,
+                "Simulation of {:?} can't step a grid type of {:?}",
+                dimension, expected
+            ),
+            FDTDError::Length {
+                field,
+                len,
+                expected,
+            } => write!(
+                f,
+                "{:?} or its coefficients have {} nodes rather than {}",
+                field, len, expected
+            ),
+            FDTDError::Coefficient { field, pos, reason } => {
+                write!(f, "Coefficient of {:?} at {:?} is {}", field, pos, reason)
+            }
+            FDTDError::Combination { first, second } => {
+                write!(f, "{} can't be combined with {}", first, second)
+            }
+            FDTDError::Region { min, max, reason } => {
+                write!(f, "Region between {:?} and {:?} {}", min, max, reason)
+            }
+            FDTDError::Shape {
+                part,
+                field,
+                len,
+                expected,
+            } => write!(
+                f,
+                "{:?} nodes of {} number {} rather than the grid's {}",
+                field, part, len, expected
+            ),
+            FDTDError::Polarization { axis } => write!(
+                f,
+                "Wave can't be polarized along its direction of propagation, {:?}",
+                axis
+            ),
+            FDTDError::Node { field, pos } => {
+                write!(f, "Grid has no {:?} node at {:?}", field, pos)
+            }
+            FDTDError::Interval { interval } => {
+                write!(f, "Interval of {} steps is less than one step", interval)
+            }
+            FDTDError::Unstable {
+                step,
+                field,
+                pos,
+                value,
+            } => write!(
+                f,
+                "Unstable value {} of {:?} at {:?} after {} steps",
+                value, field, pos, step
+            ),
         }
     }
 }
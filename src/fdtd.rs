@@ -1,25 +1,63 @@
 // src/fdtd.rs
 //! Referenced from "Understanding the Finite-Difference Time-Domain Method"
 //! by John. B Schneider; https://eecs.wsu.edu/~schneidj/ufdtd/ufdtd.pdf.
+use crate::bloch::Bloch;
+use crate::conductor::Conductors;
+use crate::cpml::{Cpml, Profile};
+use crate::dispersion::Dispersion;
 use crate::error;
-use crate::grid::Grid;
+use crate::grid::{Field, Grid, GridType};
+use crate::observer::Observer;
+use crate::sources::Source;
 use crate::step;
+use crate::watchdog::Watchdog;
 use fdtd_futhark::{Array_f64_1d, Array_f64_2d, Array_f64_3d, FutharkContext};
+use std::marker::PhantomData;
 
 /// TM^z or TE^z.
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Polarization {
     Magnetic,
     Electric,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum GridDimension {
     One,
     Two(Polarization),
     Three,
 }
 
+impl GridDimension {
+    /// Check that 'g' has the axes and components the dimension updates.
+    pub(crate) fn check(self, g: &Grid) -> Result<(), error::FDTDError> {
+        let (axes, fields) = match self {
+            GridDimension::One => (1, &[Field::Hy, Field::Ez][..]),
+            GridDimension::Two(Polarization::Magnetic) => {
+                (2, &[Field::Hx, Field::Hy, Field::Ez][..])
+            }
+            GridDimension::Two(Polarization::Electric) => {
+                (2, &[Field::Hz, Field::Ex, Field::Ey][..])
+            }
+            GridDimension::Three => (3, &Field::ALL[..]),
+        };
+
+        let sizes = [g.x_sz, g.y_sz, g.z_sz];
+        let shaped = sizes
+            .iter()
+            .enumerate()
+            .all(|(a, s)| (*s > 0) == (a < axes));
+        if !shaped || !fields.iter().all(|f| g.has(*f)) {
+            return Err(error::FDTDError::Dimension {
+                dimension: self,
+                sizes,
+            });
+        }
+
+        Ok(())
+    }
+}
+
 impl Default for GridDimension {
     fn default() -> Self {
         GridDimension::One
@@ -72,6 +110,19 @@ struct FutharkArr2d {
     ceze: Array_f64_2d,
 }
 
+// hz, chzh, chze, ex, cexh, cexe, ey, ceyh, ceye
+struct FutharkArr2dTe {
+    hz: Array_f64_2d,
+    chzh: Array_f64_2d,
+    chze: Array_f64_2d,
+    ex: Array_f64_2d,
+    cexh: Array_f64_2d,
+    cexe: Array_f64_2d,
+    ey: Array_f64_2d,
+    ceyh: Array_f64_2d,
+    ceye: Array_f64_2d,
+}
+
 struct FutharkArr3d {
     hx: Array_f64_3d,
     chxh: Array_f64_3d,
@@ -136,13 +187,117 @@ fn arr3d_into_vec(v: &mut Vec<f64>, arr: Array_f64_3d) -> Result<(), error::FDTD
     Ok(())
 }
 
+/// Step a 3D grid and its CPML 'n' times with Futhark, keeping the psi
+/// arrays on the device between steps.
+fn step_cpml_3d_futhark(
+    ctx: &mut FutharkContext,
+    arr: FutharkArr3d,
+    c: &mut Cpml,
+    g: &mut Grid,
+    n: usize,
+) -> Result<(), error::FDTDError> {
+    let dev = *ctx;
+    let dim = [g.x_sz as i64, g.y_sz as i64, g.z_sz as i64];
+    let psi = |v: &Vec<f64>| Array_f64_3d::from_vec(dev, v, &dim);
+    let prof = |p: &Profile, len: usize| Array_f64_2d::from_vec(dev, &p.rows(), &[8, len as i64]);
+
+    let (
+        hx_arr,
+        hy_arr,
+        hz_arr,
+        ex_arr,
+        ey_arr,
+        ez_arr,
+        hxy,
+        hxz,
+        hyx,
+        hyz,
+        hzx,
+        hzy,
+        exy,
+        exz,
+        eyx,
+        eyz,
+        ezx,
+        ezy,
+    ) = ctx.step_multiple_cpml_3d(
+        n as i64,
+        arr.hx,
+        arr.chxh,
+        arr.chxe,
+        arr.hy,
+        arr.chyh,
+        arr.chye,
+        arr.hz,
+        arr.chzh,
+        arr.chze,
+        arr.ex,
+        arr.cexh,
+        arr.cexe,
+        arr.ey,
+        arr.ceyh,
+        arr.ceye,
+        arr.ez,
+        arr.cezh,
+        arr.ceze,
+        psi(&c.psi_hxy)?,
+        psi(&c.psi_hxz)?,
+        psi(&c.psi_hyx)?,
+        psi(&c.psi_hyz)?,
+        psi(&c.psi_hzx)?,
+        psi(&c.psi_hzy)?,
+        psi(&c.psi_exy)?,
+        psi(&c.psi_exz)?,
+        psi(&c.psi_eyx)?,
+        psi(&c.psi_eyz)?,
+        psi(&c.psi_ezx)?,
+        psi(&c.psi_ezy)?,
+        prof(&c.x, g.x_sz)?,
+        prof(&c.y, g.y_sz)?,
+        prof(&c.z, g.z_sz)?,
+    )?;
+
+    // Update 'Hx', 'Hy', 'Hz', 'Ex', 'Ey', and 'Ez' within the grid.
+    arr3d_into_vec(&mut g.hx, hx_arr)?;
+    arr3d_into_vec(&mut g.hy, hy_arr)?;
+    arr3d_into_vec(&mut g.hz, hz_arr)?;
+    arr3d_into_vec(&mut g.ex, ex_arr)?;
+    arr3d_into_vec(&mut g.ey, ey_arr)?;
+    arr3d_into_vec(&mut g.ez, ez_arr)?;
+
+    // Update the psi arrays within the CPML.
+    arr3d_into_vec(&mut c.psi_hxy, hxy)?;
+    arr3d_into_vec(&mut c.psi_hxz, hxz)?;
+    arr3d_into_vec(&mut c.psi_hyx, hyx)?;
+    arr3d_into_vec(&mut c.psi_hyz, hyz)?;
+    arr3d_into_vec(&mut c.psi_hzx, hzx)?;
+    arr3d_into_vec(&mut c.psi_hzy, hzy)?;
+    arr3d_into_vec(&mut c.psi_exy, exy)?;
+    arr3d_into_vec(&mut c.psi_exz, exz)?;
+    arr3d_into_vec(&mut c.psi_eyx, eyx)?;
+    arr3d_into_vec(&mut c.psi_eyz, eyz)?;
+    arr3d_into_vec(&mut c.psi_ezx, ezx)?;
+    arr3d_into_vec(&mut c.psi_ezy, ezy)?;
+
+    Ok(())
+}
+
+// Native update of one of the fields of a grid.
+type FieldUpdate = fn(&mut Grid);
+
 // TODO: Closures that fit type of A/B must be specified for compilation,
 // even if the function is a NOP. This requires the programmer to write a NOP
 // function then pass it; is this avoidable?
-pub struct FDTDSim<A, B>
+/// A simulation stepping grids of type 'G': either a 'Grid', stepped in the
+/// dimension the simulation is created with, or a grid type fixed to one
+/// dimension, such as 'Grid2dTm', so that grids of another dimension can't
+/// be passed to it. The post-{magnetic, electric} functions, sources and
+/// observers see the grid's storage.
+pub struct FDTDSim<A, B, G = Grid>
 where
     A: FnMut(usize, &mut Grid), // post-magnetic update.
     B: FnMut(usize, &mut Grid), // post-electric update.
+    G: GridType,
 {
     dimension: GridDimension,
     backend: Backend,
@@ -152,34 +307,73 @@ where
     // boxed closures, etc. What's the most performant/flexible?
     post_magnetic: Option<A>,
     post_electric: Option<B>,
+
+    // Axes along which the grid is periodic.
+    periodic: [bool; 3],
+
+    // Bloch-periodic boundaries, in place of any periodic axes.
+    bloch: Option<Bloch>,
+
+    // Absorbing boundary applied directly after the field updates.
+    cpml: Option<Cpml>,
+
+    // Dispersive media, corrected before the CPML is applied.
+    dispersion: Option<Dispersion>,
+
+    // Perfect conductors, applied after the CPML.
+    conductors: Option<Conductors>,
+
+    // Sources applied after the conductors.
+    sources: Vec<Box<dyn Source>>,
+
+    // Observers called once each step has completed.
+    observers: Vec<Box<dyn Observer>>,
+
+    // Checks the fields for instability every so many steps.
+    watchdog: Option<Watchdog>,
+
     time: usize,
+    grid: PhantomData<G>,
 }
 
-impl<A, B> Default for FDTDSim<A, B>
+impl<A, B, G> Default for FDTDSim<A, B, G>
 where
     A: FnMut(usize, &mut Grid),
     B: FnMut(usize, &mut Grid),
+    G: GridType,
 {
     fn default() -> Self {
         FDTDSim {
-            dimension: GridDimension::default(),
+            dimension: G::DIMENSION.unwrap_or_default(),
             backend: Backend::default(),
             backend_context: None,
             post_magnetic: None,
             post_electric: None,
+            periodic: [false; 3],
+            bloch: None,
+            cpml: None,
+            dispersion: None,
+            conductors: None,
+            sources: Vec::new(),
+            observers: Vec::new(),
+            watchdog: None,
             time: 0,
+            grid: PhantomData,
         }
     }
 }
 
 // TODO: See above note in FDTDSim about A/B specification when we want NOP
 // functions.
-impl<A, B> FDTDSim<A, B>
+impl<A, B, G> FDTDSim<A, B, G>
 where
     A: FnMut(usize, &mut Grid),
     B: FnMut(usize, &mut Grid),
+    G: GridType,
 {
-    /// Create a new FDTDSimulation.
+    /// Create a new FDTDSimulation. Grid types fixed to one dimension
+    /// decide it, and 'dimension' may be left out for them; any other
+    /// dimension is an error.
     pub fn new(
         dimension: Option<GridDimension>,
         backend: Option<Backend>,
@@ -191,18 +385,37 @@ where
         // TODO: What's the cost of building a new context on each step call?
         // We can avoid making 'step' functions mutable if we just build a new
         // mutable futhark context.
+        let dimension = match (G::DIMENSION, dimension) {
+            (Some(expected), Some(d)) if expected != d => {
+                return Err(error::FDTDError::GridType {
+                    dimension: d,
+                    expected,
+                })
+            }
+            (expected, d) => expected.or(d).unwrap_or_default(),
+        };
+
         let context = match backend {
             Some(Backend::Futhark) => Some(FutharkContext::new()?),
             _ => None,
         };
 
         Ok(FDTDSim {
-            dimension: dimension.unwrap_or_default(),
+            dimension,
             backend: backend.unwrap_or_default(),
             backend_context: context,
             post_magnetic: a,
             post_electric: b,
+            periodic: [false; 3],
+            bloch: None,
+            cpml: None,
+            dispersion: None,
+            conductors: None,
+            sources: Vec::new(),
+            observers: Vec::new(),
+            watchdog: None,
             time: time.unwrap_or(0),
+            grid: PhantomData,
         })
     }
 
@@ -214,13 +427,229 @@ where
         self.post_electric = f;
     }
 
+    /// Set the axes (x, y, z) along which the grid is periodic; the update
+    /// stencil wraps around them rather than stopping at the faces of the
+    /// grid. Axes the grid doesn't have are ignored.
+    pub fn set_periodic(&mut self, periodic: [bool; 3]) {
+        self.periodic = periodic;
+    }
+
+    /// Set Bloch-periodic boundaries, which take the place of any periodic
+    /// axes. The imaginary part of the fields is stepped alongside the grid,
+    /// through the regular updates and any conductors; sources and the
+    /// post-{magnetic, electric} functions only see the real part. They are
+    /// always stepped natively, and can't be combined with a CPML or
+    /// dispersive media; setting them alongside either fails with
+    /// 'FDTDError::Combination'.
+    pub fn set_bloch(&mut self, b: Option<Bloch>) -> Result<(), error::FDTDError> {
+        if b.is_some() {
+            if self.cpml.is_some() {
+                return Err(error::FDTDError::Combination {
+                    first: "Bloch-periodic boundaries",
+                    second: "a CPML",
+                });
+            }
+            if self.dispersion.is_some() {
+                return Err(error::FDTDError::Combination {
+                    first: "Bloch-periodic boundaries",
+                    second: "dispersive media",
+                });
+            }
+        }
+
+        self.bloch = b;
+        Ok(())
+    }
+
+    pub fn bloch(&self) -> Option<&Bloch> {
+        self.bloch.as_ref()
+    }
+
+    /// Set the CPML lining the grid; it is applied after each field update
+    /// and before the post-{magnetic, electric} functions. It can't be
+    /// combined with Bloch-periodic boundaries.
+    pub fn set_cpml(&mut self, c: Option<Cpml>) -> Result<(), error::FDTDError> {
+        if c.is_some() && self.bloch.is_some() {
+            return Err(error::FDTDError::Combination {
+                first: "a CPML",
+                second: "Bloch-periodic boundaries",
+            });
+        }
+
+        self.cpml = c;
+        Ok(())
+    }
+
+    /// Set the dispersive media within the grid; their polarization
+    /// currents are applied after each electric update, before the CPML.
+    /// They can't be combined with Bloch-periodic boundaries.
+    pub fn set_dispersion(&mut self, d: Option<Dispersion>) -> Result<(), error::FDTDError> {
+        if d.is_some() && self.bloch.is_some() {
+            return Err(error::FDTDError::Combination {
+                first: "dispersive media",
+                second: "Bloch-periodic boundaries",
+            });
+        }
+
+        self.dispersion = d;
+        Ok(())
+    }
+
+    /// Set the perfect conductors within the grid; they are applied after
+    /// the CPML and before any sources.
+    pub fn set_conductors(&mut self, c: Option<Conductors>) {
+        self.conductors = c;
+    }
+
+    /// Add a source; sources are applied after the CPML and conductors, and
+    /// before the post-{magnetic, electric} functions.
+    pub fn add_source(&mut self, s: impl Source + 'static) {
+        self.sources.push(Box::new(s));
+    }
+
+    /// Add an observer; observers are called once each step has completed,
+    /// after the post-electric function. Share one through an
+    /// `Rc<RefCell<_>>` to read it back.
+    pub fn add_observer(&mut self, o: impl Observer + 'static) {
+        self.observers.push(Box::new(o));
+    }
+
+    /// Set a watchdog, which checks the fields once every so many steps
+    /// have been taken and halts stepping with 'FDTDError::Unstable' once
+    /// they turn unstable. Batches of steps stepped within Futhark are split
+    /// at each check.
+    pub fn set_watchdog(&mut self, w: Option<Watchdog>) {
+        self.watchdog = w;
+    }
+
+    /// Call the observers once a step has completed.
+    fn observe(&mut self, g: &Grid) {
+        for o in &mut self.observers {
+            o.observe(self.time, g);
+        }
+    }
+
+    /// Apply the parts of the magnetic update always computed natively.
+    fn apply_magnetic(&mut self, g: &mut Grid) {
+        if let Some(b) = &mut self.bloch {
+            b.magnetic(g);
+        } else if self.periodic.contains(&true) {
+            step::magnetic_periodic(g, self.periodic);
+        }
+
+        if let Some(c) = &mut self.cpml {
+            c.magnetic(g);
+        }
+
+        if let Some(c) = &self.conductors {
+            c.magnetic(g);
+            if let Some(b) = &mut self.bloch {
+                c.magnetic(b.imag_mut());
+            }
+        }
+
+        for s in &self.sources {
+            s.magnetic(self.time, g);
+        }
+    }
+
+    /// Prepare for the parts of the electric update always computed
+    /// natively.
+    fn pre_electric(&mut self, g: &mut Grid) {
+        if let Some(d) = &mut self.dispersion {
+            d.pre_electric(g);
+        }
+    }
+
+    /// Apply the parts of the electric update always computed natively.
+    fn apply_electric(&mut self, g: &mut Grid) {
+        if let Some(b) = &mut self.bloch {
+            b.electric(g);
+        } else if self.periodic.contains(&true) {
+            step::electric_periodic(g, self.periodic);
+        }
+
+        if let Some(d) = &mut self.dispersion {
+            d.electric(g);
+        }
+
+        if let Some(c) = &mut self.cpml {
+            c.electric(g);
+        }
+
+        if let Some(c) = &self.conductors {
+            c.electric(g);
+            if let Some(b) = &mut self.bloch {
+                c.electric(b.imag_mut());
+            }
+        }
+
+        for s in &self.sources {
+            s.electric(self.time, g);
+        }
+    }
+
     /// Perform a single step for a given grid.
-    pub fn step(&mut self, g: &mut Grid) -> Result<(), error::FDTDError> {
+    pub fn step(&mut self, g: &mut G) -> Result<(), error::FDTDError> {
         self.step_mul(g, 1)
     }
 
+    /// Check that 'g' can be stepped in the simulation's dimension: it has
+    /// the axes and components the dimension updates, passes
+    /// 'Grid::validate', has the nodes the sources excite and the
+    /// dispersive media hold, and has the shape the conductors and the
+    /// imaginary part of any Bloch-periodic fields were built for. This is
+    /// checked each time the grid is stepped.
+    pub fn validate(&self, g: &Grid) -> Result<(), error::FDTDError> {
+        self.dimension.check(g)?;
+        g.validate()?;
+
+        if let Some(d) = &self.dispersion {
+            d.check(g)?;
+        }
+        if let Some(c) = &self.conductors {
+            c.check(g)?;
+        }
+        if let Some(b) = &self.bloch {
+            b.check(g)?;
+        }
+
+        for s in &self.sources {
+            s.check(g)?;
+        }
+
+        Ok(())
+    }
+
     /// Perform multiple steps for a given grid.
-    pub fn step_mul(&mut self, g: &mut Grid, n: usize) -> Result<(), error::FDTDError> {
+    pub fn step_mul(&mut self, g: &mut G, n: usize) -> Result<(), error::FDTDError> {
+        let g = g.grid_mut();
+        self.validate(g)?;
+
+        let interval = match &self.watchdog {
+            Some(w) => w.interval(),
+            None => return self.step_batch(g, n),
+        };
+
+        // Step up to each check in turn.
+        let mut left = n;
+        while left > 0 {
+            let k = left.min(interval - self.time % interval);
+            self.step_batch(g, k)?;
+            left -= k;
+
+            if self.time.is_multiple_of(interval) {
+                if let Some(w) = &mut self.watchdog {
+                    w.check(self.time, g)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Perform 'n' steps with whichever backend suits the simulation.
+    fn step_batch(&mut self, g: &mut Grid, n: usize) -> Result<(), error::FDTDError> {
         match self.backend {
             Backend::Native => {
                 for _ in 0..n {
@@ -230,16 +659,45 @@ where
                 Ok(())
             }
 
+            // Bloch-periodic boundaries are always stepped natively.
+            Backend::Futhark if self.bloch.is_some() => {
+                for _ in 0..n {
+                    self.step_native(g)?;
+                }
+
+                Ok(())
+            }
+
             Backend::Futhark => {
                 // If we have post-{magnetic, electric}, we have to perform
                 // those with native code. If not, we can do 'n' number
                 // of steps and likely save on copying over the boundary.
                 // The code for this has to be explicit; I doubt the compiler
-                // can infer anything due to FFI.
-                match (&self.post_magnetic, &self.post_electric) {
-                    (None, None) => self.step_mul_futhark(g, n),
-
-                    (None, _some) => {
+                // can infer anything due to FFI. Futhark only steps a CPML
+                // on 3D grids; otherwise, it's applied natively between the
+                // two halves of a step, as are dispersive media and sources;
+                // observers are called natively after each step.
+                // Conductors and periodic boundaries are each handled within
+                // Futhark unless there's anything else to do natively.
+                let native_cpml =
+                    self.cpml.is_some() && !matches!(self.dimension, GridDimension::Three);
+                let masked = self.conductors.is_some();
+                let periodic = self.periodic.contains(&true);
+                let native = native_cpml
+                    || self.dispersion.is_some()
+                    || !self.sources.is_empty()
+                    || !self.observers.is_empty()
+                    || ((masked || periodic) && self.cpml.is_some())
+                    || (masked && periodic);
+
+                match (&self.post_magnetic, &self.post_electric, native) {
+                    (None, None, false) if masked => self.step_mul_masked_futhark(g, n),
+
+                    (None, None, false) if periodic => self.step_mul_periodic_futhark(g, n),
+
+                    (None, None, false) => self.step_mul_futhark(g, n),
+
+                    (None, _some, false) if !masked && !periodic => {
                         for _ in 0..n {
                             self.step_single_futhark(g)?;
                         }
@@ -247,7 +705,7 @@ where
                         Ok(())
                     }
 
-                    (_some, _) => {
+                    (_, _, _) => {
                         for _ in 0..n {
                             self.step_split_futhark(g)?;
                         }
@@ -317,6 +775,37 @@ where
         })
     }
 
+    /// Build arrays needed for a 2D TE^z Futhark step.
+    // TODO: See the above note about caching.
+    fn build_2d_te_futhark_arr(
+        &mut self,
+        g: &Grid,
+        ctx: &mut FutharkContext,
+    ) -> Result<FutharkArr2dTe, error::FDTDError> {
+        let dim = [g.x_sz as i64, g.y_sz as i64];
+        let hz = Array_f64_2d::from_vec(*ctx, &g.hz, &dim)?;
+        let chzh = Array_f64_2d::from_vec(*ctx, &g.chzh, &dim)?;
+        let chze = Array_f64_2d::from_vec(*ctx, &g.chze, &dim)?;
+        let ex = Array_f64_2d::from_vec(*ctx, &g.ex, &dim)?;
+        let cexh = Array_f64_2d::from_vec(*ctx, &g.cexh, &dim)?;
+        let cexe = Array_f64_2d::from_vec(*ctx, &g.cexe, &dim)?;
+        let ey = Array_f64_2d::from_vec(*ctx, &g.ey, &dim)?;
+        let ceyh = Array_f64_2d::from_vec(*ctx, &g.ceyh, &dim)?;
+        let ceye = Array_f64_2d::from_vec(*ctx, &g.ceye, &dim)?;
+
+        Ok(FutharkArr2dTe {
+            hz,
+            chzh,
+            chze,
+            ex,
+            cexh,
+            cexe,
+            ey,
+            ceyh,
+            ceye,
+        })
+    }
+
     /// Build arrays needed for a 3D Futhark step.
     // TODO: See the above note about caching.
     fn build_3d_futhark_arr(
@@ -397,6 +886,25 @@ where
                 arr2d_into_vec(&mut g.ez, ez_arr)?;
             }
 
+            GridDimension::Two(Polarization::Electric) => {
+                let arr = self.build_2d_te_futhark_arr(g, &mut ctx)?;
+                let (hz_arr, ex_arr, ey_arr) = ctx.step_2d_te(
+                    arr.hz, arr.chzh, arr.chze, arr.ex, arr.cexh, arr.cexe, arr.ey, arr.ceyh,
+                    arr.ceye,
+                )?;
+
+                // Update 'Hz', 'Ex', and 'Ey' within the grid.
+                arr2d_into_vec(&mut g.hz, hz_arr)?;
+                arr2d_into_vec(&mut g.ex, ex_arr)?;
+                arr2d_into_vec(&mut g.ey, ey_arr)?;
+            }
+
+            GridDimension::Three if self.cpml.is_some() => {
+                let arr = self.build_3d_futhark_arr(g, &mut ctx)?;
+                let c = self.cpml.as_mut().expect("No CPML!");
+                step_cpml_3d_futhark(&mut ctx, arr, c, g, 1)?;
+            }
+
             GridDimension::Three => {
                 let arr = self.build_3d_futhark_arr(g, &mut ctx)?;
                 let (hx_arr, hy_arr, hz_arr, ex_arr, ey_arr, ez_arr) = ctx.step_3d(
@@ -413,11 +921,15 @@ where
                 arr3d_into_vec(&mut g.ey, ey_arr)?;
                 arr3d_into_vec(&mut g.ez, ez_arr)?;
             }
+        }
 
-            _ => panic!("Unimplemented!"),
+        // Perform the post-electric step.
+        if let Some(v) = &mut self.post_electric {
+            v(self.time, g);
         }
 
         self.time += 1;
+        self.observe(g);
         Ok(())
     }
 
@@ -452,6 +964,14 @@ where
                 arr2d_into_vec(&mut g.hy, hy_arr)?;
             }
 
+            GridDimension::Two(Polarization::Electric) => {
+                let arr = self.build_2d_te_futhark_arr(g, &mut ctx)?;
+                let hz_arr = ctx.hz_step_2d_te(arr.hz, arr.chzh, arr.chze, arr.ex, arr.ey)?;
+
+                // Update 'Hz' within the grid.
+                arr2d_into_vec(&mut g.hz, hz_arr)?;
+            }
+
             GridDimension::Three => {
                 let arr = self.build_3d_futhark_arr(g, &mut ctx)?;
                 let (hx_arr, hy_arr, hz_arr) = ctx.magnetic_step_3d(
@@ -464,15 +984,18 @@ where
                 arr3d_into_vec(&mut g.hy, hy_arr)?;
                 arr3d_into_vec(&mut g.hz, hz_arr)?;
             }
-            _ => panic!("Unimplemented!"),
         }
 
+        self.apply_magnetic(g);
+
         // Perform the post-magnetic step.
         match &mut self.post_magnetic {
             Some(v) => v(self.time, g),
             None => (),
         }
 
+        self.pre_electric(g);
+
         // Perform the electric step.
         match self.dimension {
             GridDimension::One => {
@@ -492,6 +1015,18 @@ where
                 arr2d_into_vec(&mut g.ez, ez_arr)?;
             }
 
+            GridDimension::Two(Polarization::Electric) => {
+                let arr = self.build_2d_te_futhark_arr(g, &mut ctx)?;
+                // ex, cexh, cexe, ey, ceyh, ceye, hz.
+                let (ex_arr, ey_arr) = ctx.electric_step_2d_te(
+                    arr.ex, arr.cexh, arr.cexe, arr.ey, arr.ceyh, arr.ceye, arr.hz,
+                )?;
+
+                // Update 'Ex' and 'Ey' within the grid.
+                arr2d_into_vec(&mut g.ex, ex_arr)?;
+                arr2d_into_vec(&mut g.ey, ey_arr)?;
+            }
+
             GridDimension::Three => {
                 let arr = self.build_3d_futhark_arr(g, &mut ctx)?;
                 // ex, cexe, cexh, ey, ceye, ceyh, ez, ceze, cezh, hx, hy, hz
@@ -505,9 +1040,10 @@ where
                 arr3d_into_vec(&mut g.ey, ey_arr)?;
                 arr3d_into_vec(&mut g.ez, ez_arr)?;
             }
-            _ => panic!("Unimplemented!"),
         }
 
+        self.apply_electric(g);
+
         // Perform the post-electric step.
         match &mut self.post_electric {
             Some(v) => v(self.time, g),
@@ -515,6 +1051,7 @@ where
         }
 
         self.time += 1;
+        self.observe(g);
         Ok(())
     }
 
@@ -549,6 +1086,25 @@ where
                 arr2d_into_vec(&mut g.ez, ez_arr)?;
             }
 
+            GridDimension::Two(Polarization::Electric) => {
+                let arr = self.build_2d_te_futhark_arr(g, &mut ctx)?;
+                let (hz_arr, ex_arr, ey_arr) = ctx.step_multiple_2d_te(
+                    n as i64, arr.hz, arr.chzh, arr.chze, arr.ex, arr.cexh, arr.cexe, arr.ey,
+                    arr.ceyh, arr.ceye,
+                )?;
+
+                // Update 'Hz', 'Ex', and 'Ey' within the grid.
+                arr2d_into_vec(&mut g.hz, hz_arr)?;
+                arr2d_into_vec(&mut g.ex, ex_arr)?;
+                arr2d_into_vec(&mut g.ey, ey_arr)?;
+            }
+
+            GridDimension::Three if self.cpml.is_some() => {
+                let arr = self.build_3d_futhark_arr(g, &mut ctx)?;
+                let c = self.cpml.as_mut().expect("No CPML!");
+                step_cpml_3d_futhark(&mut ctx, arr, c, g, n)?;
+            }
+
             GridDimension::Three => {
                 let arr = self.build_3d_futhark_arr(g, &mut ctx)?;
                 let (hx_arr, hy_arr, hz_arr, ex_arr, ey_arr, ez_arr) = ctx.step_multiple_3d(
@@ -565,7 +1121,167 @@ where
                 arr3d_into_vec(&mut g.ey, ey_arr)?;
                 arr3d_into_vec(&mut g.ez, ez_arr)?;
             }
-            _ => panic!("Unimplemented!"),
+        }
+
+        self.time += n;
+        Ok(())
+    }
+
+    /// Perform 'n' steps with Futhark, masking the fields within perfect
+    /// conductors after each half step.
+    fn step_mul_masked_futhark(&mut self, g: &mut Grid, n: usize) -> Result<(), error::FDTDError> {
+        let mut ctx = self.backend_context.expect("No FutharkContext!");
+        let c = self.conductors.as_ref().expect("No conductors!");
+
+        match self.dimension {
+            GridDimension::One => {
+                let dim = [g.x_sz as i64];
+                let mask = |f: Field| Array_f64_1d::from_vec(ctx, c.mask(f), &dim);
+                let (mask_hy, mask_ez) = (mask(Field::Hy)?, mask(Field::Ez)?);
+
+                let arr = self.build_1d_futhark_arr(g, &mut ctx)?;
+                let (hy_arr, ez_arr) = ctx.step_multiple_masked_1d(
+                    n as i64, arr.hy, arr.chyh, arr.chye, arr.ez, arr.cezh, arr.ceze, mask_hy,
+                    mask_ez,
+                )?;
+
+                // Update 'Hy' and 'Ez' within the grid.
+                arr1d_into_vec(&mut g.hy, hy_arr)?;
+                arr1d_into_vec(&mut g.ez, ez_arr)?;
+            }
+
+            GridDimension::Two(Polarization::Magnetic) => {
+                let dim = [g.x_sz as i64, g.y_sz as i64];
+                let mask = |f: Field| Array_f64_2d::from_vec(ctx, c.mask(f), &dim);
+                let (mask_hx, mask_hy, mask_ez) =
+                    (mask(Field::Hx)?, mask(Field::Hy)?, mask(Field::Ez)?);
+
+                let arr = self.build_2d_futhark_arr(g, &mut ctx)?;
+                let (hx_arr, hy_arr, ez_arr) = ctx.step_multiple_masked_2d(
+                    n as i64, arr.hx, arr.chxh, arr.chxe, arr.hy, arr.chyh, arr.chye, arr.ez,
+                    arr.cezh, arr.ceze, mask_hx, mask_hy, mask_ez,
+                )?;
+
+                // Update 'Hx', 'Hy', and 'Ez' within the grid.
+                arr2d_into_vec(&mut g.hx, hx_arr)?;
+                arr2d_into_vec(&mut g.hy, hy_arr)?;
+                arr2d_into_vec(&mut g.ez, ez_arr)?;
+            }
+
+            GridDimension::Two(Polarization::Electric) => {
+                let dim = [g.x_sz as i64, g.y_sz as i64];
+                let mask = |f: Field| Array_f64_2d::from_vec(ctx, c.mask(f), &dim);
+                let (mask_hz, mask_ex, mask_ey) =
+                    (mask(Field::Hz)?, mask(Field::Ex)?, mask(Field::Ey)?);
+
+                let arr = self.build_2d_te_futhark_arr(g, &mut ctx)?;
+                let (hz_arr, ex_arr, ey_arr) = ctx.step_multiple_masked_2d_te(
+                    n as i64, arr.hz, arr.chzh, arr.chze, arr.ex, arr.cexh, arr.cexe, arr.ey,
+                    arr.ceyh, arr.ceye, mask_hz, mask_ex, mask_ey,
+                )?;
+
+                // Update 'Hz', 'Ex', and 'Ey' within the grid.
+                arr2d_into_vec(&mut g.hz, hz_arr)?;
+                arr2d_into_vec(&mut g.ex, ex_arr)?;
+                arr2d_into_vec(&mut g.ey, ey_arr)?;
+            }
+
+            GridDimension::Three => {
+                let dim = [g.x_sz as i64, g.y_sz as i64, g.z_sz as i64];
+                let mask = |f: Field| Array_f64_3d::from_vec(ctx, c.mask(f), &dim);
+                let (mask_hx, mask_hy, mask_hz) =
+                    (mask(Field::Hx)?, mask(Field::Hy)?, mask(Field::Hz)?);
+                let (mask_ex, mask_ey, mask_ez) =
+                    (mask(Field::Ex)?, mask(Field::Ey)?, mask(Field::Ez)?);
+
+                let arr = self.build_3d_futhark_arr(g, &mut ctx)?;
+                let (hx_arr, hy_arr, hz_arr, ex_arr, ey_arr, ez_arr) = ctx
+                    .step_multiple_masked_3d(
+                        n as i64, arr.hx, arr.chxh, arr.chxe, arr.hy, arr.chyh, arr.chye, arr.hz,
+                        arr.chzh, arr.chze, arr.ex, arr.cexh, arr.cexe, arr.ey, arr.ceyh, arr.ceye,
+                        arr.ez, arr.cezh, arr.ceze, mask_hx, mask_hy, mask_hz, mask_ex, mask_ey,
+                        mask_ez,
+                    )?;
+
+                // Update 'Hx', 'Hy', 'Hz', 'Ex', 'Ey', and 'Ez' within the grid.
+                arr3d_into_vec(&mut g.hx, hx_arr)?;
+                arr3d_into_vec(&mut g.hy, hy_arr)?;
+                arr3d_into_vec(&mut g.hz, hz_arr)?;
+                arr3d_into_vec(&mut g.ex, ex_arr)?;
+                arr3d_into_vec(&mut g.ey, ey_arr)?;
+                arr3d_into_vec(&mut g.ez, ez_arr)?;
+            }
+        }
+
+        self.time += n;
+        Ok(())
+    }
+
+    /// Perform 'n' steps with Futhark, wrapping the update stencil around
+    /// the periodic axes.
+    fn step_mul_periodic_futhark(
+        &mut self,
+        g: &mut Grid,
+        n: usize,
+    ) -> Result<(), error::FDTDError> {
+        let mut ctx = self.backend_context.expect("No FutharkContext!");
+        let [px, py, pz] = self.periodic;
+
+        match self.dimension {
+            GridDimension::One => {
+                let arr = self.build_1d_futhark_arr(g, &mut ctx)?;
+                let (hy_arr, ez_arr) = ctx.step_multiple_periodic_1d(
+                    n as i64, px, arr.hy, arr.chyh, arr.chye, arr.ez, arr.cezh, arr.ceze,
+                )?;
+
+                // Update 'Hy' and 'Ez' within the grid.
+                arr1d_into_vec(&mut g.hy, hy_arr)?;
+                arr1d_into_vec(&mut g.ez, ez_arr)?;
+            }
+
+            GridDimension::Two(Polarization::Magnetic) => {
+                let arr = self.build_2d_futhark_arr(g, &mut ctx)?;
+                let (hx_arr, hy_arr, ez_arr) = ctx.step_multiple_periodic_2d(
+                    n as i64, px, py, arr.hx, arr.chxh, arr.chxe, arr.hy, arr.chyh, arr.chye,
+                    arr.ez, arr.cezh, arr.ceze,
+                )?;
+
+                // Update 'Hx', 'Hy', and 'Ez' within the grid.
+                arr2d_into_vec(&mut g.hx, hx_arr)?;
+                arr2d_into_vec(&mut g.hy, hy_arr)?;
+                arr2d_into_vec(&mut g.ez, ez_arr)?;
+            }
+
+            GridDimension::Two(Polarization::Electric) => {
+                let arr = self.build_2d_te_futhark_arr(g, &mut ctx)?;
+                let (hz_arr, ex_arr, ey_arr) = ctx.step_multiple_periodic_2d_te(
+                    n as i64, px, py, arr.hz, arr.chzh, arr.chze, arr.ex, arr.cexh, arr.cexe,
+                    arr.ey, arr.ceyh, arr.ceye,
+                )?;
+
+                // Update 'Hz', 'Ex', and 'Ey' within the grid.
+                arr2d_into_vec(&mut g.hz, hz_arr)?;
+                arr2d_into_vec(&mut g.ex, ex_arr)?;
+                arr2d_into_vec(&mut g.ey, ey_arr)?;
+            }
+
+            GridDimension::Three => {
+                let arr = self.build_3d_futhark_arr(g, &mut ctx)?;
+                let (hx_arr, hy_arr, hz_arr, ex_arr, ey_arr, ez_arr) = ctx
+                    .step_multiple_periodic_3d(
+                        n as i64, px, py, pz, arr.hx, arr.chxh, arr.chxe, arr.hy, arr.chyh,
+                        arr.chye, arr.hz, arr.chzh, arr.chze, arr.ex, arr.cexh, arr.cexe, arr.ey,
+                        arr.ceyh, arr.ceye, arr.ez, arr.cezh, arr.ceze,
+                    )?;
+
+                // Update 'Hx', 'Hy', 'Hz', 'Ex', 'Ey', and 'Ez' within the grid.
+                arr3d_into_vec(&mut g.hx, hx_arr)?;
+                arr3d_into_vec(&mut g.hy, hy_arr)?;
+                arr3d_into_vec(&mut g.hz, hz_arr)?;
+                arr3d_into_vec(&mut g.ex, ex_arr)?;
+                arr3d_into_vec(&mut g.ey, ey_arr)?;
+                arr3d_into_vec(&mut g.ez, ez_arr)?;
+            }
         }
 
         self.time += n;
@@ -574,24 +1290,35 @@ where
 
     /// Perform a native step for a given grid.
     fn step_native(&mut self, g: &mut Grid) -> Result<(), error::FDTDError> {
-        match self.dimension {
-            GridDimension::One => step::magnetic_1d(g),
-            GridDimension::Two(Polarization::Magnetic) => step::magnetic_2d(g),
-            GridDimension::Three => step::magnetic_3d(g),
-            _ => panic!("Unimplemented!"),
+        let (magnetic, electric): (FieldUpdate, FieldUpdate) = match self.dimension {
+            GridDimension::One => (step::magnetic_1d, step::electric_1d),
+            GridDimension::Two(Polarization::Magnetic) => (step::magnetic_2d, step::electric_2d),
+            GridDimension::Two(Polarization::Electric) => {
+                (step::magnetic_2d_te, step::electric_2d_te)
+            }
+            GridDimension::Three => (step::magnetic_3d, step::electric_3d),
         };
 
+        magnetic(g);
+        if let Some(b) = &mut self.bloch {
+            magnetic(b.imag_mut());
+        }
+
+        self.apply_magnetic(g);
+
         match &mut self.post_magnetic {
             Some(v) => v(self.time, g),
             None => (),
         }
 
-        match self.dimension {
-            GridDimension::One => step::electric_1d(g),
-            GridDimension::Two(Polarization::Magnetic) => step::electric_2d(g),
-            GridDimension::Three => step::electric_3d(g),
-            _ => panic!("Unimplemented!"),
-        };
+        self.pre_electric(g);
+
+        electric(g);
+        if let Some(b) = &mut self.bloch {
+            electric(b.imag_mut());
+        }
+
+        self.apply_electric(g);
 
         match &mut self.post_electric {
             Some(v) => v(self.time, g),
@@ -599,6 +1326,7 @@ where
         }
 
         self.time += 1;
+        self.observe(g);
         Ok(())
     }
 }
@@ -0,0 +1,242 @@
+// src/flux.rs
+//! Poynting flux monitors, which record the flow of power through a plane
+//! of a grid (a line, on 2D grids, or a point, on 1D grids), both as it
+//! happens and as a spectrum.
+//!
+//! The normal component of 'E × H' is found on the plane of the electric
+//! field nodes. The tangential electric field lies on the plane, while the
+//! tangential magnetic field sits half a cell either side of it and is
+//! averaged across; each product is then taken where both components sit
+//! within the plane. The instantaneous flux is found half a step before the
+//! electric field, when the magnetic field is sampled, by averaging the
+//! electric field over the step.
+//!
+//! Reflection and transmission spectra come from a normalization run with
+//! the same source and no scatterer. The ratio of a spectrum to that of the
+//! normalization run gives the transmission; subtracting the fields of the
+//! normalization run from a monitor facing the source leaves the reflected
+//! flux, which flows backwards.
+use crate::dft::{self, add, mul, scale, Complex};
+use crate::error::FDTDError;
+use crate::grid::{Field, Grid};
+use crate::monitor::Region;
+use crate::observer::Observer;
+
+/// A term 'sign E_e H_h' of the normal component of 'E × H'.
+#[derive(Debug, Clone)]
+struct Term {
+    e: Field,
+    h: Field,
+    sign: f64,
+
+    // Index of the electric field at each node, and of the magnetic field
+    // either side of it.
+    e_indices: Vec<usize>,
+    h_indices: Vec<(usize, usize)>,
+
+    // Phasors of each component, frequency by frequency, node by node.
+    e_phasors: Vec<Vec<Complex>>,
+    h_phasors: Vec<Vec<Complex>>,
+}
+
+impl Term {
+    fn electric(&self, g: &Grid) -> Vec<f64> {
+        let e = g.field(self.e);
+        self.e_indices.iter().map(|&i| e[i]).collect()
+    }
+
+    fn magnetic(&self, g: &Grid) -> Vec<f64> {
+        let h = g.field(self.h);
+        self.h_indices
+            .iter()
+            .map(|&(i, j)| 0.5 * (h[i] + h[j]))
+            .collect()
+    }
+}
+
+/// Poynting flux through the nodes of a region one node thick along 'axis',
+/// taken to flow along the axis; each node stands for a unit area (or
+/// length, on 2D grids).
+#[derive(Debug, Clone)]
+pub struct FluxMonitor {
+    axis: usize,
+    nodes: Vec<[usize; 3]>,
+    omegas: Vec<f64>,
+    terms: Vec<Term>,
+
+    // Electric field of each term at the last step.
+    previous: Option<Vec<Vec<f64>>>,
+
+    // Instantaneous flux, from time 'start' on.
+    start: f64,
+    series: Vec<f64>,
+}
+
+impl FluxMonitor {
+    /// Monitor the flux through 'region' of 'g' along 'axis', at the
+    /// frequencies 'omegas'. The region must be one node thick along the
+    /// axis, and not on the grid's first node along it; otherwise, or if
+    /// the grid lacks the axis, this fails with 'FDTDError::Region'.
+    pub fn new(g: &Grid, region: Region, axis: usize, omegas: &[f64]) -> Result<Self, FDTDError> {
+        let error = |reason| {
+            Err(FDTDError::Region {
+                min: region.min,
+                max: region.max,
+                reason,
+            })
+        };
+        let sz = [g.x_sz, g.y_sz, g.z_sz];
+        if sz[axis] == 0 {
+            return error("lies across an axis the grid doesn't have");
+        }
+        if region.min[axis] == 0 || region.max[axis] != region.min[axis] + 1 {
+            return error("must be one node thick, past the first node");
+        }
+
+        let nodes = region.nodes(g);
+        let zero = vec![vec![(0.0, 0.0); nodes.len()]; omegas.len()];
+        let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+        let terms = [(b, c, 1.0), (c, b, -1.0)]
+            .into_iter()
+            .map(|(e, h, sign)| (Field::electric(e), Field::magnetic(h), sign))
+            .filter(|(e, h, _)| g.has(*e) && g.has(*h))
+            .map(|(e, h, sign)| Term {
+                e,
+                h,
+                sign,
+                e_indices: nodes.iter().map(|p| g.index(*p)).collect(),
+                h_indices: nodes
+                    .iter()
+                    .map(|p| {
+                        let mut q = *p;
+                        q[axis] -= 1;
+                        (g.index(*p), g.index(q))
+                    })
+                    .collect(),
+                e_phasors: zero.clone(),
+                h_phasors: zero.clone(),
+            })
+            .collect();
+
+        Ok(FluxMonitor {
+            axis,
+            nodes,
+            omegas: omegas.to_vec(),
+            terms,
+            previous: None,
+            start: 0.0,
+            series: Vec::new(),
+        })
+    }
+
+    /// Monitor the flux through every node of 'g' at 'index' along 'axis'.
+    pub fn plane(g: &Grid, axis: usize, index: usize, omegas: &[f64]) -> Result<Self, FDTDError> {
+        FluxMonitor::new(g, Region::plane(g, axis, index), axis, omegas)
+    }
+
+    pub fn axis(&self) -> usize {
+        self.axis
+    }
+
+    pub fn omegas(&self) -> &[f64] {
+        &self.omegas
+    }
+
+    /// Positions of the nodes covered.
+    pub fn nodes(&self) -> &[[usize; 3]] {
+        &self.nodes
+    }
+
+    /// Instantaneous flux, a value per step from time 'start()' on.
+    pub fn series(&self) -> &[f64] {
+        &self.series
+    }
+
+    /// Time of the first value of the instantaneous flux.
+    pub fn start(&self) -> f64 {
+        self.start
+    }
+
+    /// Flux at each frequency, 'Re(E × H*)' of the phasors; the energy
+    /// carried through is '1/π' of its integral over frequency.
+    pub fn spectrum(&self) -> Vec<f64> {
+        (0..self.omegas.len())
+            .map(|i| {
+                self.terms
+                    .iter()
+                    .map(|t| {
+                        let sum: f64 = t.e_phasors[i]
+                            .iter()
+                            .zip(&t.h_phasors[i])
+                            .map(|(e, h)| mul(*e, (h.0, -h.1)).0)
+                            .sum();
+                        t.sign * sum
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Subtract the phasors of 'other', a monitor of the same plane and
+    /// frequencies in another run, leaving the spectrum of the difference
+    /// in fields between the runs. The instantaneous flux is left as is.
+    pub fn subtract(&mut self, other: &FluxMonitor) {
+        assert!(
+            self.axis == other.axis && self.nodes == other.nodes && self.omegas == other.omegas,
+            "Flux monitors must cover the same plane and frequencies"
+        );
+
+        for (t, o) in self.terms.iter_mut().zip(&other.terms) {
+            let pairs = t
+                .e_phasors
+                .iter_mut()
+                .zip(&o.e_phasors)
+                .chain(t.h_phasors.iter_mut().zip(&o.h_phasors));
+            for (p, q) in pairs {
+                for (a, b) in p.iter_mut().zip(q) {
+                    *a = add(*a, scale(*b, -1.0));
+                }
+            }
+        }
+    }
+}
+
+impl Observer for FluxMonitor {
+    fn observe(&mut self, time: usize, g: &Grid) {
+        let e: Vec<Vec<f64>> = self.terms.iter().map(|t| t.electric(g)).collect();
+        let h: Vec<Vec<f64>> = self.terms.iter().map(|t| t.magnetic(g)).collect();
+
+        for ((t, e), h) in self.terms.iter_mut().zip(&e).zip(&h) {
+            let (te, th) = (dft::sample_time(t.e, time), dft::sample_time(t.h, time));
+            for (i, &omega) in self.omegas.iter().enumerate() {
+                let (ke, kh) = (dft::kernel(omega, te), dft::kernel(omega, th));
+                for (p, v) in t.e_phasors[i].iter_mut().zip(e) {
+                    *p = add(*p, scale(ke, *v));
+                }
+                for (p, v) in t.h_phasors[i].iter_mut().zip(h) {
+                    *p = add(*p, scale(kh, *v));
+                }
+            }
+        }
+
+        if let Some(previous) = &self.previous {
+            if self.series.is_empty() {
+                self.start = time as f64 - 0.5;
+            }
+
+            let flux = self
+                .terms
+                .iter()
+                .zip(previous.iter().zip(&e))
+                .zip(&h)
+                .map(|((t, (p, e)), h)| {
+                    let sum: f64 = (0..h.len()).map(|n| 0.5 * (p[n] + e[n]) * h[n]).sum();
+                    t.sign * sum
+                })
+                .sum();
+            self.series.push(flux);
+        }
+
+        self.previous = Some(e);
+    }
+}
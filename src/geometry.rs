@@ -0,0 +1,227 @@
+// src/geometry.rs
+//! Shapes which paint materials into a grid through `Grid::paint`.
+//!
+//! Positions are measured in cells, with node (m, n, p) of a grid at
+//! (m, n, p). 2D shapes extend indefinitely along z, and 2D grids are
+//! sampled at z = 0.
+use crate::grid::Axis;
+
+/// A region of space.
+pub trait Shape {
+    /// Whether the point 'p' lies within the shape.
+    fn contains(&self, p: [f64; 3]) -> bool;
+
+    /// The points within either shape.
+    fn union<S: Shape>(self, other: S) -> Union<Self, S>
+    where
+        Self: Sized,
+    {
+        Union(self, other)
+    }
+
+    /// The points within this shape but not within 'other'.
+    fn difference<S: Shape>(self, other: S) -> Difference<Self, S>
+    where
+        Self: Sized,
+    {
+        Difference(self, other)
+    }
+}
+
+impl<S: Shape + ?Sized> Shape for Box<S> {
+    fn contains(&self, p: [f64; 3]) -> bool {
+        (**self).contains(p)
+    }
+}
+
+impl<S: Shape + ?Sized> Shape for &S {
+    fn contains(&self, p: [f64; 3]) -> bool {
+        (**self).contains(p)
+    }
+}
+
+/// An axis-aligned rectangle in the xy-plane, corners included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rectangle {
+    pub min: [f64; 2],
+    pub max: [f64; 2],
+}
+
+impl Rectangle {
+    pub fn new(min: [f64; 2], max: [f64; 2]) -> Self {
+        Rectangle { min, max }
+    }
+}
+
+impl Shape for Rectangle {
+    fn contains(&self, p: [f64; 3]) -> bool {
+        (0..2).all(|a| self.min[a] <= p[a] && p[a] <= self.max[a])
+    }
+}
+
+/// An axis-aligned box, corners included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Block {
+    pub min: [f64; 3],
+    pub max: [f64; 3],
+}
+
+impl Block {
+    pub fn new(min: [f64; 3], max: [f64; 3]) -> Self {
+        Block { min, max }
+    }
+}
+
+impl Shape for Block {
+    fn contains(&self, p: [f64; 3]) -> bool {
+        (0..3).all(|a| self.min[a] <= p[a] && p[a] <= self.max[a])
+    }
+}
+
+/// A circle in the xy-plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub center: [f64; 2],
+    pub radius: f64,
+}
+
+impl Circle {
+    pub fn new(center: [f64; 2], radius: f64) -> Self {
+        Circle { center, radius }
+    }
+}
+
+impl Shape for Circle {
+    fn contains(&self, p: [f64; 3]) -> bool {
+        let (x, y) = (p[0] - self.center[0], p[1] - self.center[1]);
+        x * x + y * y <= self.radius * self.radius
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sphere {
+    pub center: [f64; 3],
+    pub radius: f64,
+}
+
+impl Sphere {
+    pub fn new(center: [f64; 3], radius: f64) -> Self {
+        Sphere { center, radius }
+    }
+}
+
+impl Shape for Sphere {
+    fn contains(&self, p: [f64; 3]) -> bool {
+        let d: f64 = (0..3).map(|a| (p[a] - self.center[a]).powi(2)).sum();
+        d <= self.radius * self.radius
+    }
+}
+
+/// A cylinder along 'axis', centred on 'center' and 'length' long.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cylinder {
+    pub center: [f64; 3],
+    pub axis: Axis,
+    pub radius: f64,
+    pub length: f64,
+}
+
+impl Cylinder {
+    pub fn new(center: [f64; 3], axis: Axis, radius: f64, length: f64) -> Self {
+        Cylinder {
+            center,
+            axis,
+            radius,
+            length,
+        }
+    }
+}
+
+impl Shape for Cylinder {
+    fn contains(&self, p: [f64; 3]) -> bool {
+        let a = self.axis.index();
+        let d: f64 = (0..3)
+            .filter(|t| *t != a)
+            .map(|t| (p[t] - self.center[t]).powi(2))
+            .sum();
+
+        d <= self.radius * self.radius && (p[a] - self.center[a]).abs() <= self.length / 2.0
+    }
+}
+
+/// The points on the side of a plane through 'point' which 'normal'
+/// points into, the plane included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HalfSpace {
+    pub point: [f64; 3],
+    pub normal: [f64; 3],
+}
+
+impl HalfSpace {
+    pub fn new(point: [f64; 3], normal: [f64; 3]) -> Self {
+        HalfSpace { point, normal }
+    }
+}
+
+impl Shape for HalfSpace {
+    fn contains(&self, p: [f64; 3]) -> bool {
+        let d: f64 = (0..3)
+            .map(|a| (p[a] - self.point[a]) * self.normal[a])
+            .sum();
+        d >= 0.0
+    }
+}
+
+/// A simple polygon in the xy-plane; the last vertex joins the first.
+/// Points on the edges may fall on either side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    pub vertices: Vec<[f64; 2]>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<[f64; 2]>) -> Self {
+        assert!(vertices.len() >= 3, "A polygon needs at least 3 vertices!");
+        Polygon { vertices }
+    }
+}
+
+impl Shape for Polygon {
+    // Count the edges crossed by a ray from 'p' in the +x direction.
+    fn contains(&self, p: [f64; 3]) -> bool {
+        let (x, y) = (p[0], p[1]);
+        let n = self.vertices.len();
+
+        let mut inside = false;
+        for k in 0..n {
+            let [x0, y0] = self.vertices[k];
+            let [x1, y1] = self.vertices[(k + 1) % n];
+
+            if (y0 > y) != (y1 > y) && x < x0 + (y - y0) / (y1 - y0) * (x1 - x0) {
+                inside = !inside;
+            }
+        }
+
+        inside
+    }
+}
+
+/// The points within either of two shapes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Union<A, B>(pub A, pub B);
+
+impl<A: Shape, B: Shape> Shape for Union<A, B> {
+    fn contains(&self, p: [f64; 3]) -> bool {
+        self.0.contains(p) || self.1.contains(p)
+    }
+}
+
+/// The points within the first shape but not within the second.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference<A, B>(pub A, pub B);
+
+impl<A: Shape, B: Shape> Shape for Difference<A, B> {
+    fn contains(&self, p: [f64; 3]) -> bool {
+        self.0.contains(p) && !self.1.contains(p)
+    }
+}
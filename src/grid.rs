@@ -1,42 +1,143 @@
 // src/grid.rs
+use crate::error::FDTDError;
+use crate::fdtd::{GridDimension, Polarization};
+use crate::geometry::Shape;
+use crate::material::Material;
+use crate::view::{self, FieldView, FieldViewMut};
 use serde::Serialize;
+use std::ops::Deref;
 
 /// Characteristic impedance of free space.
 pub const IMP0: f64 = 377.0;
 
+/// Field components of a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Field {
+    Hx,
+    Hy,
+    Hz,
+    Ex,
+    Ey,
+    Ez,
+}
+
+impl Field {
+    pub const ALL: [Field; 6] = [
+        Field::Hx,
+        Field::Hy,
+        Field::Hz,
+        Field::Ex,
+        Field::Ey,
+        Field::Ez,
+    ];
+
+    /// Whether the component is part of the electric field.
+    pub fn is_electric(&self) -> bool {
+        matches!(self, Field::Ex | Field::Ey | Field::Ez)
+    }
+
+    /// Magnetic component along 'axis'.
+    pub(crate) fn magnetic(axis: usize) -> Field {
+        [Field::Hx, Field::Hy, Field::Hz][axis]
+    }
+
+    /// Electric component along 'axis'.
+    pub(crate) fn electric(axis: usize) -> Field {
+        [Field::Ex, Field::Ey, Field::Ez][axis]
+    }
+
+    /// Axis the component points along.
+    pub(crate) fn axis(&self) -> usize {
+        match self {
+            Field::Hx | Field::Ex => 0,
+            Field::Hy | Field::Ey => 1,
+            Field::Hz | Field::Ez => 2,
+        }
+    }
+
+    /// Offset of the component from its node, in cells. Electric components
+    /// sit half a cell along their own axis; magnetic components half a cell
+    /// along the other two.
+    pub fn offset(&self) -> [f64; 3] {
+        let mut offset = [0.0; 3];
+        for (a, o) in offset.iter_mut().enumerate() {
+            if (a == self.axis()) == self.is_electric() {
+                *o = 0.5;
+            }
+        }
+        offset
+    }
+}
+
+/// Axes of a 3D grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub(crate) fn index(self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Clone, PartialEq)]
 pub struct Grid {
-    // TODO: For 1d, 2d, etc. we don't need all of these vectors; at this
-    // point, we allocate what ends up being a 0-length(?) vector on the heap.
-    // This could be an Option instead?
+    // Components and coefficients a dimension doesn't update are left
+    // empty, which doesn't allocate, and aren't serialized. 'Grid1d',
+    // 'Grid2dTm', 'Grid2dTe' and 'Grid3d' fix the dimension of a grid.
     // TODO: Rather than expose these as public, provide getter/setter
     // functions?
     pub x_sz: usize,
     pub y_sz: usize,
     pub z_sz: usize,
 
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub hx: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub chxh: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub chxe: Vec<f64>,
 
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub hy: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub chyh: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub chye: Vec<f64>,
 
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub hz: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub chzh: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub chze: Vec<f64>,
 
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub ex: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub cexe: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub cexh: Vec<f64>,
 
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub ey: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub ceye: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub ceyh: Vec<f64>,
 
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub ez: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub ceze: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub cezh: Vec<f64>,
 
     pub cdtds: f64,
@@ -86,25 +187,352 @@ impl Grid {
         v
     }
 
-    /// Build a new 1D grid.
+    /// Number of nodes in each field vector.
+    pub(crate) fn len(&self) -> usize {
+        self.x_sz * self.y_sz.max(1) * self.z_sz.max(1)
+    }
+
+    /// Index of the node at 'pos' within the field vectors; panics if it
+    /// lies outside the grid.
+    pub(crate) fn index(&self, pos: [usize; 3]) -> usize {
+        view::index(self.shape(), pos)
+    }
+
+    /// Position of the node at index 'i' within the field vectors.
+    pub(crate) fn position(&self, i: usize) -> [usize; 3] {
+        view::position(self.shape(), i)
+    }
+
+    /// Number of nodes along each axis, one for axes the grid doesn't have.
+    pub fn shape(&self) -> [usize; 3] {
+        [self.x_sz, self.y_sz.max(1), self.z_sz.max(1)]
+    }
+
+    /// Positions of every node, in the order the field vectors store them.
+    pub fn positions(&self) -> impl Iterator<Item = [usize; 3]> {
+        view::positions(self.shape())
+    }
+
+    /// View of component 'f', if the grid has it.
+    pub fn view(&self, f: Field) -> Option<FieldView<'_>> {
+        let shape = self.shape();
+        self.has(f).then(|| FieldView::new(shape, self.field(f)))
+    }
+
+    /// Mutable view of component 'f', if the grid has it.
+    pub fn view_mut(&mut self, f: Field) -> Option<FieldViewMut<'_>> {
+        let shape = self.shape();
+        let (v, _) = self.field_mut(f);
+        (!v.is_empty()).then(|| FieldViewMut::new(shape, v))
+    }
+
+    /// Value of component 'f' at 'pos', if the grid has the component and
+    /// the position lies within it.
+    pub fn get(&self, f: Field, pos: [usize; 3]) -> Option<f64> {
+        self.view(f).and_then(|v| v.get(pos))
+    }
+
+    /// Node of component 'f' at 'pos', if the grid has the component and
+    /// the position lies within it.
+    pub fn get_mut(&mut self, f: Field, pos: [usize; 3]) -> Option<&mut f64> {
+        let shape = self.shape();
+        let (v, _) = self.field_mut(f);
+        if v.is_empty() {
+            return None;
+        }
+        FieldViewMut::new(shape, v).into_mut(pos)
+    }
+
+    /// Courant number limit of the grid's dimension, '1/sqrt(d)'.
+    pub fn courant_limit(&self) -> f64 {
+        courant_limit(self.dims())
+    }
+
+    fn dims(&self) -> usize {
+        1 + (self.y_sz > 0) as usize + (self.z_sz > 0) as usize
+    }
+
+    /// Check the Courant number is within the limit of the grid's dimension.
+    pub(crate) fn check_courant(&self) -> Result<(), FDTDError> {
+        let limit = self.courant_limit();
+
+        // Leave room for rounding in Courant numbers given as the limit.
+        if self.cdtds > 0.0 && self.cdtds <= limit * (1.0 + 1e-12) {
+            Ok(())
+        } else {
+            Err(FDTDError::Courant {
+                cdtds: self.cdtds,
+                limit,
+            })
+        }
+    }
+
+    /// Check the grid can be stepped stably: its Courant number is within
+    /// the limit of its dimension, and each component and its coefficients
+    /// cover the grid. The coefficients of each node must be finite, with
+    /// the component's own coefficient at most one in magnitude and
+    /// non-negative curl coefficients; the product of the largest electric
+    /// and magnetic curl coefficients, the square of the local Courant
+    /// number, must be within the square of the limit.
+    pub fn validate(&self) -> Result<(), FDTDError> {
+        self.check_courant()?;
+        let limit = self.courant_limit();
+        let fields: Vec<Field> = Field::ALL
+            .iter()
+            .copied()
+            .filter(|f| self.has(*f))
+            .collect();
+
+        let len = self.len();
+        for &f in &fields {
+            let (own, curl) = self.coefficients(f);
+            for n in [self.field(f).len(), own.len(), curl.len()] {
+                if n != len {
+                    return Err(FDTDError::Length {
+                        field: f,
+                        len: n,
+                        expected: len,
+                    });
+                }
+            }
+        }
+
+        let invalid = |field: Field, i: usize, reason: &'static str| {
+            Err(FDTDError::Coefficient {
+                field,
+                pos: self.position(i),
+                reason,
+            })
+        };
+
+        for i in 0..len {
+            // Largest electric and magnetic curl coefficients.
+            let mut largest = [(0.0, Field::Ez), (0.0, Field::Hy)];
+            for &f in &fields {
+                let (own, curl) = self.coefficients(f);
+                if !own[i].is_finite() || !curl[i].is_finite() {
+                    return invalid(f, i, "not finite");
+                }
+                if own[i].abs() > 1.0 {
+                    return invalid(f, i, "greater than one in magnitude");
+                }
+                if curl[i] < 0.0 {
+                    return invalid(f, i, "negative");
+                }
+
+                let l = &mut largest[!f.is_electric() as usize];
+                if curl[i] > l.0 {
+                    *l = (curl[i], f);
+                }
+            }
+
+            if largest[0].0 * largest[1].0 > limit * limit * (1.0 + 1e-12) {
+                return invalid(largest[0].1, i, "beyond the local Courant limit");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return a field component along with the coefficient applied to the
+    /// curl of the other field in its update.
+    pub(crate) fn field_mut(&mut self, f: Field) -> (&mut Vec<f64>, &Vec<f64>) {
+        match f {
+            Field::Hx => (&mut self.hx, &self.chxe),
+            Field::Hy => (&mut self.hy, &self.chye),
+            Field::Hz => (&mut self.hz, &self.chze),
+            Field::Ex => (&mut self.ex, &self.cexh),
+            Field::Ey => (&mut self.ey, &self.ceyh),
+            Field::Ez => (&mut self.ez, &self.cezh),
+        }
+    }
+
+    /// Return the coefficients of a field component and of the curl of the
+    /// other field in its update, e.g. ('ceze', 'cezh').
+    pub(crate) fn coefficients_mut(&mut self, f: Field) -> (&mut Vec<f64>, &mut Vec<f64>) {
+        match f {
+            Field::Hx => (&mut self.chxh, &mut self.chxe),
+            Field::Hy => (&mut self.chyh, &mut self.chye),
+            Field::Hz => (&mut self.chzh, &mut self.chze),
+            Field::Ex => (&mut self.cexe, &mut self.cexh),
+            Field::Ey => (&mut self.ceye, &mut self.ceyh),
+            Field::Ez => (&mut self.ceze, &mut self.cezh),
+        }
+    }
+
+    /// Set the update coefficients of component 'f' at index 'i' from a
+    /// material, if the grid has the component.
+    fn set_component_material(&mut self, f: Field, i: usize, m: &Material) {
+        let (own, curl) = if f.is_electric() {
+            m.electric_coefficients(self.cdtds)
+        } else {
+            m.magnetic_coefficients(self.cdtds)
+        };
+
+        let (c_own, c_curl) = self.coefficients_mut(f);
+        if !c_own.is_empty() {
+            c_own[i] = own;
+            c_curl[i] = curl;
+        }
+    }
+
+    /// Set the update coefficients of the cell at 'pos' from its material.
+    /// Only the coefficients of components the grid has are touched; panics
+    /// if 'pos' lies outside the grid.
+    pub fn set_material(&mut self, pos: [usize; 3], m: &Material) {
+        let i = self.index(pos);
+        for f in Field::ALL {
+            self.set_component_material(f, i, m);
+        }
+    }
+
+    /// Set the update coefficients of every cell from the material 'f'
+    /// returns for its position.
+    pub fn set_materials(&mut self, f: impl Fn([usize; 3]) -> Material) {
+        for x in 0..self.x_sz {
+            for y in 0..self.y_sz.max(1) {
+                for z in 0..self.z_sz.max(1) {
+                    self.set_material([x, y, z], &f([x, y, z]));
+                }
+            }
+        }
+    }
+
+    /// Return a field component.
+    pub(crate) fn field(&self, f: Field) -> &Vec<f64> {
+        match f {
+            Field::Hx => &self.hx,
+            Field::Hy => &self.hy,
+            Field::Hz => &self.hz,
+            Field::Ex => &self.ex,
+            Field::Ey => &self.ey,
+            Field::Ez => &self.ez,
+        }
+    }
+
+    /// Return the coefficients of a field component and of the curl of the
+    /// other field in its update, e.g. ('ceze', 'cezh').
+    pub(crate) fn coefficients(&self, f: Field) -> (&Vec<f64>, &Vec<f64>) {
+        match f {
+            Field::Hx => (&self.chxh, &self.chxe),
+            Field::Hy => (&self.chyh, &self.chye),
+            Field::Hz => (&self.chzh, &self.chze),
+            Field::Ex => (&self.cexe, &self.cexh),
+            Field::Ey => (&self.ceye, &self.ceyh),
+            Field::Ez => (&self.ceze, &self.cezh),
+        }
+    }
+
+    /// Whether the grid has component 'f'.
+    pub(crate) fn has(&self, f: Field) -> bool {
+        !self.field(f).is_empty()
+    }
+
+    /// Value of component 'f' at 'pos', in cells, interpolated linearly
+    /// between its nearest nodes. Positions beyond the grid are clamped to
+    /// it, dimensions the grid doesn't have are ignored, and components it
+    /// doesn't have are zero.
+    pub fn sample(&self, f: Field, pos: [f64; 3]) -> f64 {
+        let v = self.field(f);
+        if v.is_empty() {
+            return 0.0;
+        }
+
+        // Lower node along each axis, and the weight of the upper one.
+        let sz = [self.x_sz, self.y_sz, self.z_sz];
+        let mut lo = [0; 3];
+        let mut w = [0.0; 3];
+        for (a, offset) in f.offset().iter().enumerate().filter(|(a, _)| sz[*a] > 1) {
+            let x = (pos[a] - offset).clamp(0.0, (sz[a] - 1) as f64);
+            lo[a] = (x.floor() as usize).min(sz[a] - 2);
+            w[a] = x - lo[a] as f64;
+        }
+
+        (0..8)
+            .filter_map(|corner: usize| {
+                let mut p = lo;
+                let mut weight = 1.0;
+                for a in 0..3 {
+                    if corner >> a & 1 == 1 {
+                        p[a] += 1;
+                        weight *= w[a];
+                    } else {
+                        weight *= 1.0 - w[a];
+                    }
+                }
+
+                (weight != 0.0).then(|| weight * v[self.index(p)])
+            })
+            .sum()
+    }
+
+    /// List the components the grid has which lie within 'shape', along
+    /// with the position of their node. Each component is sampled at its
+    /// own position within the cell; dimensions the grid doesn't have are
+    /// sampled at 0.
+    pub(crate) fn components_in(&self, shape: &impl Shape) -> Vec<(Field, [usize; 3])> {
+        let used = [true, self.y_sz > 0, self.z_sz > 0];
+        let fields: Vec<Field> = Field::ALL.into_iter().filter(|f| self.has(*f)).collect();
+
+        let mut components = Vec::new();
+        for x in 0..self.x_sz {
+            for y in 0..self.y_sz.max(1) {
+                for z in 0..self.z_sz.max(1) {
+                    for &f in &fields {
+                        let mut p = [x as f64, y as f64, z as f64];
+                        for ((p, o), u) in p.iter_mut().zip(f.offset()).zip(used) {
+                            if u {
+                                *p += o;
+                            }
+                        }
+
+                        if shape.contains(p) {
+                            components.push((f, [x, y, z]));
+                        }
+                    }
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Paint a material into the grid wherever 'shape' contains a
+    /// component. Each component is sampled at its own position within the
+    /// cell, so the electric and magnetic coefficients of a cell on the
+    /// surface of a shape may differ.
+    pub fn paint(&mut self, shape: &impl Shape, m: &Material) {
+        for (f, pos) in self.components_in(shape) {
+            let i = self.index(pos);
+            self.set_component_material(f, i, m);
+        }
+    }
+
+    /// Build a new 1D grid, at the Courant limit of one.
     pub fn new_1d(x_sz: usize) -> Self {
+        let cdtds = 1.0;
+
         Grid {
             x_sz,
             ez: Grid::build_vec(x_sz, 0.0),
             ceze: Grid::build_vec(x_sz, 1.0),
-            cezh: Grid::build_vec(x_sz, IMP0),
+            cezh: Grid::build_vec(x_sz, cdtds * IMP0),
 
             hy: Grid::build_vec(x_sz, 0.0),
             chyh: Grid::build_vec(x_sz, 1.0),
-            chye: Grid::build_vec(x_sz, 1.0 / IMP0),
+            chye: Grid::build_vec(x_sz, cdtds / IMP0),
+
+            cdtds,
             ..Default::default()
         }
     }
 
-    /// Build a new 2D grid.
+    /// Build a new 2D TM^z grid, by default at the Courant limit of
+    /// '1/sqrt(2)'. The Courant number isn't checked until the grid is
+    /// stepped; 'Grid::try_new_2d' checks it up front.
     pub fn new_2d(x_sz: usize, y_sz: usize, cdtds: Option<f64>) -> Self {
         let len = x_sz * y_sz;
-        let cdtds = cdtds.unwrap_or(1.0 / 2.0f64.sqrt());
+        let cdtds = cdtds.unwrap_or(courant_limit(2));
 
         Grid {
             x_sz,
@@ -116,20 +544,51 @@ impl Grid {
 
             hy: Grid::build_vec(len, 0.0),
             chyh: Grid::build_vec(len, 1.0),
-            chye: Grid::build_vec(len, 1.0 / IMP0),
+            chye: Grid::build_vec(len, cdtds / IMP0),
 
             ez: Grid::build_vec(len, 0.0),
             ceze: Grid::build_vec(len, 1.0),
             cezh: Grid::build_vec(len, cdtds * IMP0),
 
+            cdtds,
             ..Default::default()
         }
     }
 
-    /// Build a new 3d grid.
+    /// Build a new 2D TE^z grid, by default at the Courant limit of
+    /// '1/sqrt(2)'. The Courant number isn't checked until the grid is
+    /// stepped; 'Grid::try_new_2d_te' checks it up front.
+    pub fn new_2d_te(x_sz: usize, y_sz: usize, cdtds: Option<f64>) -> Self {
+        let len = x_sz * y_sz;
+        let cdtds = cdtds.unwrap_or(courant_limit(2));
+
+        Grid {
+            x_sz,
+            y_sz,
+
+            hz: Grid::build_vec(len, 0.0),
+            chzh: Grid::build_vec(len, 1.0),
+            chze: Grid::build_vec(len, cdtds / IMP0),
+
+            ex: Grid::build_vec(len, 0.0),
+            cexe: Grid::build_vec(len, 1.0),
+            cexh: Grid::build_vec(len, cdtds * IMP0),
+
+            ey: Grid::build_vec(len, 0.0),
+            ceye: Grid::build_vec(len, 1.0),
+            ceyh: Grid::build_vec(len, cdtds * IMP0),
+
+            cdtds,
+            ..Default::default()
+        }
+    }
+
+    /// Build a new 3d grid, by default at the Courant limit of '1/sqrt(3)'.
+    /// The Courant number isn't checked until the grid is stepped;
+    /// 'Grid::try_new_3d' checks it up front.
     pub fn new_3d(x_sz: usize, y_sz: usize, z_sz: usize, cdtds: Option<f64>) -> Self {
         let len = x_sz * y_sz * z_sz;
-        let cdtds = cdtds.unwrap_or(1.0 / 3.0f64.sqrt());
+        let cdtds = cdtds.unwrap_or(courant_limit(3));
 
         Grid {
             x_sz,
@@ -163,4 +622,244 @@ impl Grid {
             cdtds,
         }
     }
+
+    /// Build a new 2D TM^z grid, checking it with 'Grid::validate'.
+    pub fn try_new_2d(x_sz: usize, y_sz: usize, cdtds: Option<f64>) -> Result<Self, FDTDError> {
+        let g = Grid::new_2d(x_sz, y_sz, cdtds);
+        g.validate()?;
+        Ok(g)
+    }
+
+    /// Build a new 2D TE^z grid, checking it with 'Grid::validate'.
+    pub fn try_new_2d_te(x_sz: usize, y_sz: usize, cdtds: Option<f64>) -> Result<Self, FDTDError> {
+        let g = Grid::new_2d_te(x_sz, y_sz, cdtds);
+        g.validate()?;
+        Ok(g)
+    }
+
+    /// Build a new 3d grid, checking it with 'Grid::validate'.
+    pub fn try_new_3d(
+        x_sz: usize,
+        y_sz: usize,
+        z_sz: usize,
+        cdtds: Option<f64>,
+    ) -> Result<Self, FDTDError> {
+        let g = Grid::new_3d(x_sz, y_sz, z_sz, cdtds);
+        g.validate()?;
+        Ok(g)
+    }
+}
+
+/// Courant number limit of a grid of 'dims' dimensions, '1/sqrt(dims)'.
+fn courant_limit(dims: usize) -> f64 {
+    1.0 / (dims as f64).sqrt()
+}
+
+/// A grid which can be stepped by a simulation, holding its fields in a
+/// 'Grid'. It's sealed, so the only grid types are those defined here. A
+/// simulation still hands the 'Grid' of the grid type it steps to its
+/// post-{magnetic, electric} functions, which may change anything about
+/// it; the grid is checked again on every step.
+///
+/// A simulation fixed to one grid type steps it:
+///
+/// ```
+/// use fdtd::fdtd::{Backend, FDTDSim};
+/// use fdtd::grid::{Grid, Grid2dTm};
+///
+/// let mut s: FDTDSim<fn(usize, &mut Grid), fn(usize, &mut Grid), Grid2dTm> =
+///     FDTDSim::new(None, Some(Backend::Native), None, None, None).unwrap();
+/// let mut g = Grid2dTm::new(10, 10, None);
+/// s.step(&mut g).unwrap();
+/// ```
+///
+/// but not a grid of another type:
+///
+/// ```compile_fail
+/// use fdtd::fdtd::{Backend, FDTDSim};
+/// use fdtd::grid::{Grid, Grid2dTm, Grid3d};
+///
+/// let mut s: FDTDSim<fn(usize, &mut Grid), fn(usize, &mut Grid), Grid2dTm> =
+///     FDTDSim::new(None, Some(Backend::Native), None, None, None).unwrap();
+/// let mut g = Grid3d::new(10, 10, 10, None);
+/// s.step(&mut g).unwrap();
+/// ```
+pub trait GridType: private::Sealed {
+    /// Dimension the grid is stepped in, if its type fixes one.
+    const DIMENSION: Option<GridDimension>;
+
+    fn grid(&self) -> &Grid;
+}
+
+pub(crate) mod private {
+    use super::Grid;
+
+    /// Mutable access to the 'Grid' of a grid type.
+    pub trait Sealed {
+        fn grid_mut(&mut self) -> &mut Grid;
+    }
+}
+
+impl GridType for Grid {
+    const DIMENSION: Option<GridDimension> = None;
+
+    fn grid(&self) -> &Grid {
+        self
+    }
+}
+
+impl private::Sealed for Grid {
+    fn grid_mut(&mut self) -> &mut Grid {
+        self
+    }
+}
+
+/// Define a grid type fixed to 'dimension', wrapping the 'Grid' holding its
+/// fields. It dereferences to the 'Grid' for reading, and serializes as it;
+/// outside of a simulation's post-{magnetic, electric} functions the fields
+/// and coefficients are changed through the methods it forwards.
+macro_rules! grid_type {
+    ($(#[$doc:meta])* $name:ident, $dimension:expr) => {
+        $(#[$doc])*
+        #[derive(Debug, Serialize, Clone, PartialEq)]
+        #[serde(transparent)]
+        pub struct $name(Grid);
+
+        impl $name {
+            /// The 'Grid' holding the fields.
+            pub fn into_inner(self) -> Grid {
+                self.0
+            }
+
+            /// See 'Grid::view_mut'.
+            pub fn view_mut(&mut self, f: Field) -> Option<FieldViewMut<'_>> {
+                self.0.view_mut(f)
+            }
+
+            /// See 'Grid::get_mut'.
+            pub fn get_mut(&mut self, f: Field, pos: [usize; 3]) -> Option<&mut f64> {
+                self.0.get_mut(f, pos)
+            }
+
+            /// See 'Grid::set_material'.
+            pub fn set_material(&mut self, pos: [usize; 3], m: &Material) {
+                self.0.set_material(pos, m)
+            }
+
+            /// See 'Grid::set_materials'.
+            pub fn set_materials(&mut self, f: impl Fn([usize; 3]) -> Material) {
+                self.0.set_materials(f)
+            }
+
+            /// See 'Grid::paint'.
+            pub fn paint(&mut self, shape: &impl Shape, m: &Material) {
+                self.0.paint(shape, m)
+            }
+        }
+
+        impl GridType for $name {
+            const DIMENSION: Option<GridDimension> = Some($dimension);
+
+            fn grid(&self) -> &Grid {
+                &self.0
+            }
+        }
+
+        impl private::Sealed for $name {
+            fn grid_mut(&mut self) -> &mut Grid {
+                &mut self.0
+            }
+        }
+
+        impl TryFrom<Grid> for $name {
+            type Error = FDTDError;
+
+            /// Fix the dimension of 'g', if it has the axes and components
+            /// the dimension updates.
+            fn try_from(g: Grid) -> Result<Self, FDTDError> {
+                $dimension.check(&g)?;
+                Ok($name(g))
+            }
+        }
+
+        impl Deref for $name {
+            type Target = Grid;
+
+            fn deref(&self) -> &Grid {
+                &self.0
+            }
+        }
+    };
+}
+
+grid_type!(
+    /// A 1D grid, updating 'Hy' and 'Ez'.
+    Grid1d,
+    GridDimension::One
+);
+
+grid_type!(
+    /// A 2D TM^z grid, updating 'Hx', 'Hy' and 'Ez'.
+    Grid2dTm,
+    GridDimension::Two(Polarization::Magnetic)
+);
+
+grid_type!(
+    /// A 2D TE^z grid, updating 'Hz', 'Ex' and 'Ey'.
+    Grid2dTe,
+    GridDimension::Two(Polarization::Electric)
+);
+
+grid_type!(
+    /// A 3D grid, updating every component.
+    Grid3d,
+    GridDimension::Three
+);
+
+impl Grid1d {
+    /// See 'Grid::new_1d'.
+    pub fn new(x_sz: usize) -> Self {
+        Grid1d(Grid::new_1d(x_sz))
+    }
+}
+
+impl Grid2dTm {
+    /// See 'Grid::new_2d'.
+    pub fn new(x_sz: usize, y_sz: usize, cdtds: Option<f64>) -> Self {
+        Grid2dTm(Grid::new_2d(x_sz, y_sz, cdtds))
+    }
+
+    /// See 'Grid::try_new_2d'.
+    pub fn try_new(x_sz: usize, y_sz: usize, cdtds: Option<f64>) -> Result<Self, FDTDError> {
+        Grid::try_new_2d(x_sz, y_sz, cdtds).map(Grid2dTm)
+    }
+}
+
+impl Grid2dTe {
+    /// See 'Grid::new_2d_te'.
+    pub fn new(x_sz: usize, y_sz: usize, cdtds: Option<f64>) -> Self {
+        Grid2dTe(Grid::new_2d_te(x_sz, y_sz, cdtds))
+    }
+
+    /// See 'Grid::try_new_2d_te'.
+    pub fn try_new(x_sz: usize, y_sz: usize, cdtds: Option<f64>) -> Result<Self, FDTDError> {
+        Grid::try_new_2d_te(x_sz, y_sz, cdtds).map(Grid2dTe)
+    }
+}
+
+impl Grid3d {
+    /// See 'Grid::new_3d'.
+    pub fn new(x_sz: usize, y_sz: usize, z_sz: usize, cdtds: Option<f64>) -> Self {
+        Grid3d(Grid::new_3d(x_sz, y_sz, z_sz, cdtds))
+    }
+
+    /// See 'Grid::try_new_3d'.
+    pub fn try_new(
+        x_sz: usize,
+        y_sz: usize,
+        z_sz: usize,
+        cdtds: Option<f64>,
+    ) -> Result<Self, FDTDError> {
+        Grid::try_new_3d(x_sz, y_sz, z_sz, cdtds).map(Grid3d)
+    }
 }
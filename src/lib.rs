@@ -1,8 +1,26 @@
 // src/lib.rs
 pub mod abc;
+pub mod bloch;
+pub mod conductor;
+pub mod cpml;
+pub mod dft;
+pub mod dispersion;
+pub mod energy;
 pub mod error;
 pub mod fdtd;
+pub mod flux;
+pub mod geometry;
 pub mod grid;
+pub mod material;
+pub mod monitor;
+pub mod ntff;
+pub mod observer;
+pub mod probe;
 pub mod ricker;
 pub mod snapshot;
+pub mod sources;
+pub mod tfsf;
+pub mod view;
+pub mod waveform;
+pub mod watchdog;
 mod step;
@@ -0,0 +1,89 @@
+// src/material.rs
+//! Linear, isotropic materials and the update coefficients they lead to.
+use crate::grid::IMP0;
+
+/// A linear, isotropic material.
+///
+/// The grid has no physical cell size, so conductivities are given
+/// multiplied by it: 'sigma' is 'σΔx' in siemens and 'sigma_m' is 'σ_mΔx' in
+/// ohms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Material {
+    /// Relative permittivity.
+    pub eps_r: f64,
+    /// Relative permeability.
+    pub mu_r: f64,
+    /// Electric conductivity times the cell size.
+    pub sigma: f64,
+    /// Magnetic conductivity times the cell size.
+    pub sigma_m: f64,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material::VACUUM
+    }
+}
+
+impl Material {
+    pub const VACUUM: Material = Material {
+        eps_r: 1.0,
+        mu_r: 1.0,
+        sigma: 0.0,
+        sigma_m: 0.0,
+    };
+
+    pub fn new(eps_r: f64, mu_r: f64, sigma: f64, sigma_m: f64) -> Self {
+        assert!(eps_r > 0.0 && mu_r > 0.0, "Non-positive eps_r or mu_r!");
+        assert!(sigma >= 0.0 && sigma_m >= 0.0, "Negative conductivity!");
+
+        Material {
+            eps_r,
+            mu_r,
+            sigma,
+            sigma_m,
+        }
+    }
+
+    /// A lossless, non-magnetic dielectric.
+    pub fn dielectric(eps_r: f64) -> Self {
+        Material::new(eps_r, 1.0, 0.0, 0.0)
+    }
+
+    /// A lossy material whose magnetic conductivity matches its electric
+    /// conductivity ('σ_m / μ = σ / ε'), so that its impedance is that of the
+    /// lossless material.
+    pub fn matched(eps_r: f64, mu_r: f64, sigma: f64) -> Self {
+        Material::new(eps_r, mu_r, sigma, sigma * IMP0 * IMP0 * mu_r / eps_r)
+    }
+
+    /// Electric loss per time step, 'σΔt / 2ε'.
+    pub fn electric_loss(&self, cdtds: f64) -> f64 {
+        self.sigma * cdtds * IMP0 / (2.0 * self.eps_r)
+    }
+
+    /// Magnetic loss per time step, 'σ_mΔt / 2μ'.
+    pub fn magnetic_loss(&self, cdtds: f64) -> f64 {
+        self.sigma_m * cdtds / (2.0 * IMP0 * self.mu_r)
+    }
+
+    /// Coefficients of the electric field and of the curl of the magnetic
+    /// field in the electric update, e.g. ('ceze', 'cezh').
+    pub fn electric_coefficients(&self, cdtds: f64) -> (f64, f64) {
+        let loss = self.electric_loss(cdtds);
+        (
+            (1.0 - loss) / (1.0 + loss),
+            cdtds * IMP0 / self.eps_r / (1.0 + loss),
+        )
+    }
+
+    /// Coefficients of the magnetic field and of the curl of the electric
+    /// field in the magnetic update, e.g. ('chyh', 'chye').
+    pub fn magnetic_coefficients(&self, cdtds: f64) -> (f64, f64) {
+        let loss = self.magnetic_loss(cdtds);
+        (
+            (1.0 - loss) / (1.0 + loss),
+            cdtds / IMP0 / self.mu_r / (1.0 + loss),
+        )
+    }
+}
@@ -0,0 +1,165 @@
+// src/monitor.rs
+//! Frequency-domain monitors, which accumulate running DFTs of field
+//! components over a region of a grid as the simulation runs.
+use crate::dft::{self, add, scale, Complex};
+use crate::grid::{Field, Grid};
+use crate::observer::Observer;
+use serde::Serialize;
+use std::fs;
+use std::io::prelude::*;
+
+/// Box of nodes, from 'min' up to but not including 'max'; axes the grid
+/// doesn't have are ignored. Points, lines and planes are boxes one node
+/// thick along the other axes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Region {
+    pub min: [usize; 3],
+    pub max: [usize; 3],
+}
+
+impl Region {
+    pub fn new(min: [usize; 3], max: [usize; 3]) -> Self {
+        Region { min, max }
+    }
+
+    /// The node at 'pos'.
+    pub fn point(pos: [usize; 3]) -> Self {
+        Region::new(pos, pos.map(|p| p + 1))
+    }
+
+    /// 'len' nodes along 'axis' starting from 'start'.
+    pub fn line(start: [usize; 3], axis: usize, len: usize) -> Self {
+        let mut max = start.map(|p| p + 1);
+        max[axis] = start[axis] + len;
+        Region::new(start, max)
+    }
+
+    /// Every node of 'g' at 'index' along 'axis'.
+    pub fn plane(g: &Grid, axis: usize, index: usize) -> Self {
+        let mut r = Region::all(g);
+        r.min[axis] = index;
+        r.max[axis] = index + 1;
+        r
+    }
+
+    /// Every node of 'g'.
+    pub fn all(g: &Grid) -> Self {
+        Region::new([0; 3], [g.x_sz, g.y_sz, g.z_sz])
+    }
+
+    /// Positions of the nodes of 'g' within the region, in the order of the
+    /// field vectors.
+    pub fn nodes(&self, g: &Grid) -> Vec<[usize; 3]> {
+        let sz = [g.x_sz, g.y_sz, g.z_sz];
+        let range = |a: usize| {
+            if sz[a] == 0 {
+                0..1
+            } else {
+                self.min[a].min(sz[a])..self.max[a].min(sz[a])
+            }
+        };
+
+        let mut nodes = Vec::new();
+        for x in range(0) {
+            for y in range(1) {
+                for z in range(2) {
+                    nodes.push([x, y, z]);
+                }
+            }
+        }
+        nodes
+    }
+}
+
+/// Running DFTs of a set of components over a region, at a set of
+/// frequencies in radians per time step.
+#[derive(Debug, Clone, Serialize)]
+pub struct DftMonitor {
+    fields: Vec<Field>,
+    omegas: Vec<f64>,
+    nodes: Vec<[usize; 3]>,
+    #[serde(skip)]
+    indices: Vec<usize>,
+    steps: usize,
+
+    // Phasors of each component, frequency by frequency, node by node.
+    phasors: Vec<Vec<Vec<Complex>>>,
+}
+
+impl DftMonitor {
+    /// Monitor the components 'fields' of 'g' over 'region' at the
+    /// frequencies 'omegas'; components the grid doesn't have are left out.
+    pub fn new(g: &Grid, fields: &[Field], region: Region, omegas: &[f64]) -> Self {
+        let fields: Vec<Field> = fields.iter().copied().filter(|f| g.has(*f)).collect();
+        let nodes = region.nodes(g);
+        let indices = nodes.iter().map(|p| g.index(*p)).collect();
+        let zero = vec![vec![(0.0, 0.0); nodes.len()]; omegas.len()];
+
+        DftMonitor {
+            phasors: vec![zero; fields.len()],
+            fields,
+            omegas: omegas.to_vec(),
+            nodes,
+            indices,
+            steps: 0,
+        }
+    }
+
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    pub fn omegas(&self) -> &[f64] {
+        &self.omegas
+    }
+
+    /// Positions of the nodes covered, in the order phasors are given in.
+    pub fn nodes(&self) -> &[[usize; 3]] {
+        &self.nodes
+    }
+
+    /// Number of steps accumulated so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Phasors of component 'f' at frequency 'i' over every node, if the
+    /// component is monitored.
+    pub fn phasors(&self, f: Field, i: usize) -> Option<&[Complex]> {
+        let c = self.fields.iter().position(|m| *m == f)?;
+        Some(&self.phasors[c][i])
+    }
+
+    /// Phasor of component 'f' at frequency 'i' at the node 'pos', if both
+    /// are monitored.
+    pub fn phasor(&self, f: Field, i: usize, pos: [usize; 3]) -> Option<Complex> {
+        let n = self.nodes.iter().position(|p| *p == pos)?;
+        self.phasors(f, i).map(|v| v[n])
+    }
+
+    /// Write the monitor as JSON, with each phasor as a (real, imaginary)
+    /// pair.
+    pub fn write(&self, path: &str) -> std::io::Result<()> {
+        let mut file = fs::File::create(path)?;
+        let serialized = serde_json::to_string(self).unwrap();
+        file.write_all(serialized.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl Observer for DftMonitor {
+    fn observe(&mut self, time: usize, g: &Grid) {
+        for (c, f) in self.fields.iter().enumerate() {
+            let v = g.field(*f);
+            let t = dft::sample_time(*f, time);
+            for (i, &omega) in self.omegas.iter().enumerate() {
+                let k = dft::kernel(omega, t);
+                for (p, &n) in self.phasors[c][i].iter_mut().zip(&self.indices) {
+                    *p = add(*p, scale(k, v[n]));
+                }
+            }
+        }
+
+        self.steps += 1;
+    }
+}
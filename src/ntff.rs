@@ -0,0 +1,424 @@
+// src/ntff.rs
+//! Near-to-far-field transformation (NTFF); see chapter 8 of Taflove and
+//! Hagness.
+//!
+//! The equivalent surface currents 'J = n × H' and 'M = -n × E' are recorded
+//! on the faces of a closed Huygens box within the grid as the simulation
+//! runs, and radiated to the far field afterwards; either at chosen
+//! frequencies, through running DFTs, or in the time domain along chosen
+//! directions. The box must enclose every source and scatterer, and to find
+//! a radar cross-section under a TFSF plane wave it must lie within the
+//! scattered-field region.
+//!
+//! As elsewhere, lengths are in cells, times in time steps and frequencies
+//! in radians per time step. On 2D grids the directions lie within the
+//! xy-plane, where 'θ = π/2' and so 'E_θ = -E_z', the far field is scaled by
+//! 'sqrt(ρ)' rather than 'r', and the radar cross-section is the echo width.
+use crate::dft::{self, add, mul, norm, scale, Complex};
+use crate::error::FDTDError;
+use crate::grid::{Field, Grid, IMP0};
+use crate::observer::Observer;
+use crate::waveform::Waveform;
+use std::f64::consts::PI;
+
+/// Centre of a cell face on the Huygens box, with its outward normal along
+/// 'axis'; each face has unit area (or length, on 2D grids).
+#[derive(Debug, Clone, Copy)]
+struct Patch {
+    pos: [f64; 3],
+    axis: usize,
+    sign: f64,
+}
+
+/// Faces of a Huygens box.
+#[derive(Debug, Clone)]
+struct Surface {
+    patches: Vec<Patch>,
+    center: [f64; 3],
+    planar: bool,
+    cdtds: f64,
+}
+
+impl Surface {
+    fn new(g: &Grid, min: [usize; 3], max: [usize; 3]) -> Result<Self, FDTDError> {
+        let error = |reason| Err(FDTDError::Region { min, max, reason });
+        if g.y_sz == 0 {
+            return error("needs a 2D or 3D grid");
+        }
+
+        let used = [true, true, g.z_sz > 0];
+        let sz = [g.x_sz, g.y_sz, g.z_sz];
+        for a in (0..3).filter(|a| used[*a]) {
+            if min[a] >= max[a] || max[a] >= sz[a] {
+                return error("doesn't enclose any cells within the grid");
+            }
+        }
+
+        // Centres of the cells spanning the box along an axis.
+        let span = |a: usize| -> Vec<f64> {
+            if used[a] {
+                (min[a]..max[a]).map(|u| u as f64 + 0.5).collect()
+            } else {
+                vec![0.0]
+            }
+        };
+
+        let mut patches = Vec::new();
+        for axis in (0..3).filter(|a| used[*a]) {
+            let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+            for (face, sign) in [(min[axis], -1.0), (max[axis], 1.0)] {
+                for &u in &span(b) {
+                    for &v in &span(c) {
+                        let mut pos = [0.0; 3];
+                        pos[axis] = face as f64;
+                        pos[b] = u;
+                        pos[c] = v;
+                        patches.push(Patch { pos, axis, sign });
+                    }
+                }
+            }
+        }
+
+        let center = [0, 1, 2].map(|a| {
+            if used[a] {
+                (min[a] + max[a]) as f64 / 2.0
+            } else {
+                0.0
+            }
+        });
+
+        Ok(Surface {
+            patches,
+            center,
+            planar: !used[2],
+            cdtds: g.cdtds,
+        })
+    }
+
+    /// Currents 'J' and 'M' on each patch.
+    fn currents<'a>(&'a self, g: &'a Grid) -> impl Iterator<Item = ([f64; 3], [f64; 3])> + 'a {
+        self.patches.iter().map(move |p| {
+            let e = [Field::Ex, Field::Ey, Field::Ez].map(|f| g.sample(f, p.pos));
+            let h = [Field::Hx, Field::Hy, Field::Hz].map(|f| g.sample(f, p.pos));
+            let mut n = [0.0; 3];
+            n[p.axis] = p.sign;
+            (cross(n, h), cross(n, e).map(|v| -v))
+        })
+    }
+
+    /// Projection of each patch onto 'r', relative to the centre of the box.
+    fn projections(&self, r: [f64; 3]) -> Vec<f64> {
+        self.patches
+            .iter()
+            .map(|p| (0..3).map(|a| (p.pos[a] - self.center[a]) * r[a]).sum())
+            .collect()
+    }
+
+    /// Unit vectors 'r', 'θ' and 'φ' along a direction; 'θ' is taken to be
+    /// 'π/2' on 2D grids.
+    fn basis(&self, theta: f64, phi: f64) -> [[f64; 3]; 3] {
+        let theta = if self.planar { PI / 2.0 } else { theta };
+        let (st, ct) = theta.sin_cos();
+        let (sp, cp) = phi.sin_cos();
+        [
+            [st * cp, st * sp, ct],
+            [ct * cp, ct * sp, -st],
+            [-sp, cp, 0.0],
+        ]
+    }
+}
+
+/// Far-field transformation at chosen frequencies, through running DFTs of
+/// the surface currents.
+#[derive(Debug, Clone)]
+pub struct NtffFrequency {
+    surface: Surface,
+    omegas: Vec<f64>,
+    times: Vec<usize>,
+
+    // DFTs of 'J' and 'M' on each patch, frequency by frequency.
+    j: Vec<Vec<[Complex; 3]>>,
+    m: Vec<Vec<[Complex; 3]>>,
+}
+
+impl NtffFrequency {
+    /// Record the currents on the faces of the box between the nodes 'min'
+    /// and 'max' of 'g' at the frequencies 'omegas'; the z-axis is ignored on
+    /// 2D grids. Fails with 'FDTDError::Region' on 1D grids, or if the box
+    /// doesn't enclose any cells within the grid.
+    pub fn new(
+        g: &Grid,
+        min: [usize; 3],
+        max: [usize; 3],
+        omegas: &[f64],
+    ) -> Result<Self, FDTDError> {
+        let surface = Surface::new(g, min, max)?;
+        let zero = vec![[(0.0, 0.0); 3]; surface.patches.len()];
+        Ok(NtffFrequency {
+            omegas: omegas.to_vec(),
+            times: Vec::new(),
+            j: vec![zero.clone(); omegas.len()],
+            m: vec![zero; omegas.len()],
+            surface,
+        })
+    }
+
+    pub fn omegas(&self) -> &[f64] {
+        &self.omegas
+    }
+
+    /// Far field '(E_θ, E_φ)' at frequency 'i' along '(theta, phi)', scaled
+    /// by 'r' (or 'sqrt(ρ)') and with the phase 'exp(-ikr)' left out.
+    pub fn far_field(&self, i: usize, theta: f64, phi: f64) -> (Complex, Complex) {
+        let s = &self.surface;
+        let k = self.omegas[i] / s.cdtds;
+        let [r, t, p] = s.basis(theta, phi);
+
+        // Radiation vectors 'N' and 'L' along 'θ' and 'φ'.
+        let (mut n, mut l) = ([(0.0, 0.0); 2], [(0.0, 0.0); 2]);
+        for (q, proj) in s.projections(r).into_iter().enumerate() {
+            let phase = dft::kernel(-k, proj);
+            for (b, unit) in [t, p].iter().enumerate() {
+                n[b] = add(n[b], mul(dot(&self.j[i][q], unit), phase));
+                l[b] = add(l[b], mul(dot(&self.m[i][q], unit), phase));
+            }
+        }
+
+        let f = if s.planar {
+            let (sin, cos) = (PI / 4.0).sin_cos();
+            let a = (k / (8.0 * PI)).sqrt();
+            (a * cos, a * sin)
+        } else {
+            (0.0, k / (4.0 * PI))
+        };
+
+        // E_θ = -F (L_φ + η N_θ), E_φ = F (L_θ - η N_φ)
+        let e_theta = mul(f, add(l[1], scale(n[0], IMP0)));
+        let e_phi = mul(f, add(l[0], scale(n[1], -IMP0)));
+        (scale(e_theta, -1.0), e_phi)
+    }
+
+    /// Radar cross-section (or echo width) at frequency 'i' toward
+    /// '(theta, phi)', for an incident plane wave whose DFT over the
+    /// recorded steps has magnitude 'incident'; see `incident`.
+    pub fn rcs(&self, i: usize, theta: f64, phi: f64, incident: f64) -> f64 {
+        let (e_theta, e_phi) = self.far_field(i, theta, phi);
+        let power = norm(e_theta) + norm(e_phi);
+        let scale = if self.surface.planar {
+            2.0 * PI
+        } else {
+            4.0 * PI
+        };
+        scale * power / (incident * incident)
+    }
+
+    /// Magnitude of the DFT of 'w' at frequency 'i', over the steps the
+    /// currents have been recorded at.
+    pub fn incident(&self, i: usize, w: &impl Waveform) -> f64 {
+        let omega = self.omegas[i];
+        let sum = self.times.iter().fold((0.0, 0.0), |acc, &t| {
+            add(acc, scale(dft::kernel(omega, t as f64), w.value(t as f64)))
+        });
+        norm(sum).sqrt()
+    }
+}
+
+impl Observer for NtffFrequency {
+    fn observe(&mut self, time: usize, g: &Grid) {
+        let (t_h, t_e) = (
+            dft::sample_time(Field::Hx, time),
+            dft::sample_time(Field::Ex, time),
+        );
+        for (q, (j, m)) in self.surface.currents(g).enumerate() {
+            for (i, &omega) in self.omegas.iter().enumerate() {
+                let (k_h, k_e) = (dft::kernel(omega, t_h), dft::kernel(omega, t_e));
+                for a in 0..3 {
+                    self.j[i][q][a] = add(self.j[i][q][a], scale(k_h, j[a]));
+                    self.m[i][q][a] = add(self.m[i][q][a], scale(k_e, m[a]));
+                }
+            }
+        }
+
+        self.times.push(time);
+    }
+}
+
+/// Far field along a direction over time, scaled by 'r' (or 'sqrt(ρ)');
+/// sample 'k' is at the retarded time 'start + k', measured from the
+/// moment a wave leaving the centre of the box would arrive.
+#[derive(Debug, Clone, Default)]
+pub struct FarFieldSeries {
+    pub start: f64,
+    pub e_theta: Vec<f64>,
+    pub e_phi: Vec<f64>,
+}
+
+/// Far-field transformation in the time domain along chosen directions.
+#[derive(Debug, Clone)]
+pub struct NtffTime {
+    surface: Surface,
+    directions: Vec<(f64, f64)>,
+
+    // Advance of each patch along each direction in time steps, and the
+    // delay added to each so that none is negative.
+    advances: Vec<Vec<f64>>,
+    offset: f64,
+
+    // Radiation vectors 'N' and 'L' along 'θ' and 'φ' in retarded time,
+    // direction by direction.
+    n: Vec<Vec<[f64; 2]>>,
+    l: Vec<Vec<[f64; 2]>>,
+}
+
+impl NtffTime {
+    /// Record the currents on the faces of the box between the nodes 'min'
+    /// and 'max' of 'g', radiating them along the '(theta, phi)'
+    /// 'directions'; the z-axis and 'theta' are ignored on 2D grids. Fails
+    /// as 'NtffFrequency::new' does.
+    pub fn new(
+        g: &Grid,
+        min: [usize; 3],
+        max: [usize; 3],
+        directions: &[(f64, f64)],
+    ) -> Result<Self, FDTDError> {
+        let surface = Surface::new(g, min, max)?;
+        let advances: Vec<Vec<f64>> = directions
+            .iter()
+            .map(|&(theta, phi)| {
+                let [r, _, _] = surface.basis(theta, phi);
+                surface
+                    .projections(r)
+                    .into_iter()
+                    .map(|p| p / surface.cdtds)
+                    .collect()
+            })
+            .collect();
+
+        let offset = advances.iter().flatten().fold(0.0f64, |a, v| a.max(*v)) + 1.0;
+        Ok(NtffTime {
+            directions: directions.to_vec(),
+            n: vec![Vec::new(); directions.len()],
+            l: vec![Vec::new(); directions.len()],
+            surface,
+            advances,
+            offset,
+        })
+    }
+
+    pub fn directions(&self) -> &[(f64, f64)] {
+        &self.directions
+    }
+
+    /// Far field along direction 'd'.
+    pub fn far_field(&self, d: usize) -> FarFieldSeries {
+        let (n, l) = (&self.n[d], &self.l[d]);
+        let c = self.surface.cdtds;
+
+        // r E_θ = -(1 / 4πc) ∂/∂t (L_φ + η N_θ), r E_φ = (1 / 4πc) ∂/∂t
+        // (L_θ - η N_φ); on 2D grids, 'sqrt(8πc)' and a half derivative take
+        // their place.
+        let theta: Vec<f64> = n
+            .iter()
+            .zip(l)
+            .map(|(n, l)| -(l[1] + IMP0 * n[0]))
+            .collect();
+        let phi: Vec<f64> = n.iter().zip(l).map(|(n, l)| l[0] - IMP0 * n[1]).collect();
+
+        if self.surface.planar {
+            let a = 1.0 / (8.0 * PI * c).sqrt();
+            FarFieldSeries {
+                // The Grünwald-Letnikov sum is second-order accurate a
+                // quarter step back.
+                start: -self.offset - 0.25,
+                e_theta: half_derivative(&theta).into_iter().map(|v| a * v).collect(),
+                e_phi: half_derivative(&phi).into_iter().map(|v| a * v).collect(),
+            }
+        } else {
+            let a = 1.0 / (4.0 * PI * c);
+            FarFieldSeries {
+                start: -self.offset,
+                e_theta: derivative(&theta).into_iter().map(|v| a * v).collect(),
+                e_phi: derivative(&phi).into_iter().map(|v| a * v).collect(),
+            }
+        }
+    }
+}
+
+impl Observer for NtffTime {
+    fn observe(&mut self, time: usize, g: &Grid) {
+        let t = time as f64;
+        let currents: Vec<([f64; 3], [f64; 3])> = self.surface.currents(g).collect();
+
+        for (d, &(theta, phi)) in self.directions.iter().enumerate() {
+            let [_, t_hat, p_hat] = self.surface.basis(theta, phi);
+            for ((j, m), advance) in currents.iter().zip(&self.advances[d]) {
+                // A current at time 'τ' reaches the far field at the
+                // retarded time 'τ - r·r' / c'; the magnetic field lags half
+                // a step behind.
+                let at = t - advance + self.offset;
+                let j = [dot3(j, &t_hat), dot3(j, &p_hat)];
+                let m = [dot3(m, &t_hat), dot3(m, &p_hat)];
+                deposit(&mut self.n[d], at - 0.5, j);
+                deposit(&mut self.l[d], at, m);
+            }
+        }
+    }
+}
+
+/// Add 'v' to the bins either side of 'at', weighted linearly.
+fn deposit(bins: &mut Vec<[f64; 2]>, at: f64, v: [f64; 2]) {
+    let b = at.floor() as usize;
+    let frac = at - b as f64;
+    if bins.len() < b + 2 {
+        bins.resize(b + 2, [0.0; 2]);
+    }
+
+    for a in 0..2 {
+        bins[b][a] += (1.0 - frac) * v[a];
+        bins[b + 1][a] += frac * v[a];
+    }
+}
+
+/// Central difference of a series, zero at either end.
+fn derivative(v: &[f64]) -> Vec<f64> {
+    (0..v.len())
+        .map(|k| {
+            if k == 0 || k + 1 >= v.len() {
+                0.0
+            } else {
+                0.5 * (v[k + 1] - v[k - 1])
+            }
+        })
+        .collect()
+}
+
+/// Half derivative of a series starting from rest, through the
+/// Grünwald-Letnikov sum.
+fn half_derivative(v: &[f64]) -> Vec<f64> {
+    let mut w = vec![1.0; v.len()];
+    for k in 1..v.len() {
+        w[k] = w[k - 1] * (k as f64 - 1.5) / k as f64;
+    }
+
+    (0..v.len())
+        .map(|n| (0..=n).map(|k| w[k] * v[n - k]).sum())
+        .collect()
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn dot3(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+fn dot(a: &[Complex; 3], b: &[f64; 3]) -> Complex {
+    a.iter()
+        .zip(b)
+        .fold((0.0, 0.0), |acc, (a, b)| add(acc, scale(*a, *b)))
+}
@@ -0,0 +1,20 @@
+// src/observer.rs
+//! Observers record the fields of a grid as a simulation runs.
+use crate::grid::Grid;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Something which looks at the grid once each step has completed.
+pub trait Observer {
+    /// Called with the number of steps taken so far; the electric field is
+    /// at that time and the magnetic field half a step earlier.
+    fn observe(&mut self, time: usize, g: &Grid);
+}
+
+/// An observer shared with the simulation, so that it can be read back
+/// during or after the run.
+impl<O: Observer> Observer for Rc<RefCell<O>> {
+    fn observe(&mut self, time: usize, g: &Grid) {
+        self.borrow_mut().observe(time, g);
+    }
+}
@@ -0,0 +1,198 @@
+// src/probe.rs
+//! Probes record the time series of field components at a few points of a
+//! grid, for export to CSV or JSON.
+use crate::error::FDTDError;
+use crate::grid::{Field, Grid};
+use crate::observer::Observer;
+use serde::Serialize;
+use std::fs;
+use std::io::prelude::*;
+
+/// Where a probe samples the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum Location {
+    /// The node at a position; each component is taken at its own offset
+    /// within the cell.
+    Node([usize; 3]),
+    /// A position in cells, interpolated linearly from the nearest nodes
+    /// of each component.
+    Position([f64; 3]),
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::Node([x, y, z]) => write!(f, "({} {} {})", x, y, z),
+            Location::Position([x, y, z]) => write!(f, "({} {} {})", x, y, z),
+        }
+    }
+}
+
+/// Time series of a set of components at a set of locations, recorded every
+/// 'interval' steps. The magnetic field is sampled half a step before the
+/// step it's recorded at.
+#[derive(Debug, Clone)]
+pub struct Probe {
+    fields: Vec<Field>,
+    locations: Vec<Location>,
+    interval: usize,
+
+    // Steps recorded at, and the samples taken at each: location by
+    // location, component by component.
+    times: Vec<usize>,
+    samples: Vec<f64>,
+}
+
+impl Probe {
+    /// Record the components 'fields' of 'g' at 'locations' every 'interval'
+    /// steps; components the grid doesn't have are left out. Fails with
+    /// 'FDTDError::Interval' if 'interval' is zero, and with
+    /// 'FDTDError::Node' if a node location lies outside the grid.
+    pub fn new(
+        g: &Grid,
+        fields: &[Field],
+        locations: &[Location],
+        interval: usize,
+    ) -> Result<Self, FDTDError> {
+        if interval == 0 {
+            return Err(FDTDError::Interval { interval });
+        }
+
+        let fields: Vec<Field> = fields.iter().copied().filter(|f| g.has(*f)).collect();
+        for l in locations {
+            if let Location::Node(pos) = l {
+                if let Some(&field) = fields.iter().find(|f| g.get(**f, *pos).is_none()) {
+                    return Err(FDTDError::Node { field, pos: *pos });
+                }
+            }
+        }
+
+        Ok(Probe {
+            fields,
+            locations: locations.to_vec(),
+            interval,
+            times: Vec::new(),
+            samples: Vec::new(),
+        })
+    }
+
+    /// Record the components 'fields' of 'g' at the node 'pos' every step.
+    pub fn node(g: &Grid, fields: &[Field], pos: [usize; 3]) -> Result<Self, FDTDError> {
+        Probe::new(g, fields, &[Location::Node(pos)], 1)
+    }
+
+    /// Reserve room for 'steps' more steps of the simulation.
+    pub fn reserve(&mut self, steps: usize) {
+        let n = steps / self.interval + 1;
+        self.times.reserve(n);
+        self.samples
+            .reserve(n * self.fields.len() * self.locations.len());
+    }
+
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    pub fn locations(&self) -> &[Location] {
+        &self.locations
+    }
+
+    /// Steps the samples were recorded at.
+    pub fn times(&self) -> &[usize] {
+        &self.times
+    }
+
+    /// Time series of component 'f' at location 'l', if it's recorded.
+    pub fn series(&self, f: Field, l: usize) -> Option<Vec<f64>> {
+        let c = self.fields.iter().position(|m| *m == f)?;
+        if l >= self.locations.len() {
+            return None;
+        }
+
+        let stride = self.fields.len() * self.locations.len();
+        let offset = l * self.fields.len() + c;
+        Some(
+            self.samples
+                .iter()
+                .skip(offset)
+                .step_by(stride)
+                .copied()
+                .collect(),
+        )
+    }
+
+    /// The recording as CSV: a row per step recorded, with the step
+    /// followed by a column per location and component.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("step");
+        for l in &self.locations {
+            for f in &self.fields {
+                csv += &format!(",{:?}{}", f, l);
+            }
+        }
+        csv.push('\n');
+
+        let stride = self.fields.len() * self.locations.len();
+        for (k, t) in self.times.iter().enumerate() {
+            csv += &t.to_string();
+            for v in &self.samples[k * stride..(k + 1) * stride] {
+                csv += &format!(",{}", v);
+            }
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// The recording as JSON: the components, locations and steps along
+    /// with a series per location and component.
+    pub fn to_json(&self) -> String {
+        let series: Vec<Vec<Vec<f64>>> = (0..self.locations.len())
+            .map(|l| {
+                self.fields
+                    .iter()
+                    .map(|f| self.series(*f, l).unwrap())
+                    .collect()
+            })
+            .collect();
+
+        serde_json::json!({
+            "fields": self.fields,
+            "locations": self.locations,
+            "interval": self.interval,
+            "times": self.times,
+            "series": series,
+        })
+        .to_string()
+    }
+
+    /// Write the recording to 'path' as CSV.
+    pub fn write_csv(&self, path: &str) -> std::io::Result<()> {
+        fs::File::create(path)?.write_all(self.to_csv().as_bytes())
+    }
+
+    /// Write the recording to 'path' as JSON.
+    pub fn write_json(&self, path: &str) -> std::io::Result<()> {
+        fs::File::create(path)?.write_all(self.to_json().as_bytes())
+    }
+}
+
+impl Observer for Probe {
+    fn observe(&mut self, time: usize, g: &Grid) {
+        if !time.is_multiple_of(self.interval) {
+            return;
+        }
+
+        self.times.push(time);
+        for l in &self.locations {
+            for f in &self.fields {
+                self.samples.push(match l {
+                    Location::Node(pos) => {
+                        g.get(*f, *pos).expect("Probe node lies outside the grid")
+                    }
+                    Location::Position(pos) => g.sample(*f, *pos),
+                });
+            }
+        }
+    }
+}
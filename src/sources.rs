@@ -0,0 +1,119 @@
+// src/sources.rs
+//! Point sources which attach to an `FDTDSim` through `add_source`.
+use crate::error::FDTDError;
+use crate::grid::{Field, Grid};
+use crate::view::{FieldView, FieldViewMut};
+use crate::waveform::Waveform;
+
+/// Something which excites the grid after each field update.
+pub trait Source {
+    /// Check the source can excite 'g'; called before stepping it.
+    fn check(&self, _g: &Grid) -> Result<(), FDTDError> {
+        Ok(())
+    }
+
+    /// Called after the magnetic update of time step 't', which advances the
+    /// magnetic field to 't + 1/2'.
+    fn magnetic(&self, _t: usize, _g: &mut Grid) {}
+
+    /// Called after the electric update of time step 't', which advances the
+    /// electric field to 't + 1'.
+    fn electric(&self, _t: usize, _g: &mut Grid) {}
+}
+
+/// How a point source excites its node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    /// Overwrite the field with the waveform.
+    Hard,
+    /// Add the waveform to the field.
+    Soft,
+    /// Treat the waveform as an electric (or magnetic) current density J
+    /// (or M) times the cell size, 'J * dx' (or 'M * dx'). It enters the
+    /// update as the curl does: 'ez -= cezh * (J * dx)',
+    /// 'hz -= chze * (M * dx)'.
+    Current,
+}
+
+/// A source exciting a single field node.
+pub struct PointSource<W>
+where
+    W: Waveform,
+{
+    field: Field,
+    pos: [usize; 3],
+    kind: SourceKind,
+    waveform: W,
+}
+
+impl<W> PointSource<W>
+where
+    W: Waveform,
+{
+    /// Excite 'field' at 'pos'; indices of dimensions the grid doesn't have
+    /// must be zero. The node is checked against the grid before stepping.
+    pub fn new(field: Field, pos: [usize; 3], kind: SourceKind, waveform: W) -> Self {
+        PointSource {
+            field,
+            pos,
+            kind,
+            waveform,
+        }
+    }
+
+    pub fn hard(field: Field, pos: [usize; 3], waveform: W) -> Self {
+        PointSource::new(field, pos, SourceKind::Hard, waveform)
+    }
+
+    pub fn soft(field: Field, pos: [usize; 3], waveform: W) -> Self {
+        PointSource::new(field, pos, SourceKind::Soft, waveform)
+    }
+
+    pub fn current(field: Field, pos: [usize; 3], waveform: W) -> Self {
+        PointSource::new(field, pos, SourceKind::Current, waveform)
+    }
+
+    // Excite the node; 'field' is sampled at 'time', while currents are
+    // sampled half a step earlier, between the old and new field.
+    fn apply(&self, time: f64, g: &mut Grid) {
+        let shape = g.shape();
+        let (f, coef) = g.field_mut(self.field);
+        let mut f = FieldViewMut::new(shape, f);
+
+        match self.kind {
+            SourceKind::Hard => f[self.pos] = self.waveform.value(time),
+            SourceKind::Soft => f[self.pos] += self.waveform.value(time),
+            SourceKind::Current => {
+                f[self.pos] -=
+                    FieldView::new(shape, coef)[self.pos] * self.waveform.value(time - 0.5)
+            }
+        }
+    }
+}
+
+impl<W> Source for PointSource<W>
+where
+    W: Waveform,
+{
+    fn check(&self, g: &Grid) -> Result<(), FDTDError> {
+        match g.get(self.field, self.pos) {
+            Some(_) => Ok(()),
+            None => Err(FDTDError::Node {
+                field: self.field,
+                pos: self.pos,
+            }),
+        }
+    }
+
+    fn magnetic(&self, t: usize, g: &mut Grid) {
+        if !self.field.is_electric() {
+            self.apply(t as f64 + 0.5, g);
+        }
+    }
+
+    fn electric(&self, t: usize, g: &mut Grid) {
+        if self.field.is_electric() {
+            self.apply((t + 1) as f64, g);
+        }
+    }
+}
@@ -1,14 +1,15 @@
 // src/step.rs
-use crate::grid::Grid;
+use crate::grid::{Field, Grid};
+use crate::view::layout;
 
 // TODO: Can macros be nested; can we generate these macros via a macro?
 macro_rules! dim {
     ($grid:ident, $name:ident, $x:expr, $y:expr) => {
-        $grid.$name[$x * ($grid.y_sz) + $y]
+        $grid.$name[layout!([$grid.x_sz, $grid.y_sz, 1], $x, $y, 0)]
     };
 
     ($grid:ident, $name:ident, $x:expr, $y:expr, $z:expr) => {
-        $grid.$name[($x * $grid.y_sz + $y) * $grid.z_sz + $z]
+        $grid.$name[layout!([$grid.x_sz, $grid.y_sz, $grid.z_sz], $x, $y, $z)]
     };
 }
 
@@ -40,6 +41,21 @@ pub fn magnetic_2d(g: &mut Grid) {
     }
 }
 
+// TE^Z
+pub fn magnetic_2d_te(g: &mut Grid) {
+    for m in 0..g.x_sz - 1 {
+        for n in 0..g.y_sz - 1 {
+            // hz(m, n) = chzh(m, n) * hz(m, n)
+            //  + chze(m, n) * ((ex(m, n + 1) - ex(m, n)) -
+            //      (ey(m + 1, n) - ey(m, n)))
+            dim!(g, hz, m, n) = dim!(g, chzh, m, n) * dim!(g, hz, m, n)
+                + dim!(g, chze, m, n)
+                    * ((dim!(g, ex, m, (n + 1)) - dim!(g, ex, m, n))
+                        - (dim!(g, ey, (m + 1), n) - dim!(g, ey, m, n)));
+        }
+    }
+}
+
 // 3D
 pub fn magnetic_3d(g: &mut Grid) {
     for m in 0..g.x_sz {
@@ -107,6 +123,27 @@ pub fn electric_2d(g: &mut Grid) {
     }
 }
 
+// TE^Z
+pub fn electric_2d_te(g: &mut Grid) {
+    for m in 0..g.x_sz {
+        for n in 1..g.y_sz {
+            // ex(m, n) = cexe(m, n) * ex(m, n)
+            //  + cexh(m, n) * (hz(m, n) - hz(m, (n - 1)))
+            dim!(g, ex, m, n) = dim!(g, cexe, m, n) * dim!(g, ex, m, n)
+                + dim!(g, cexh, m, n) * (dim!(g, hz, m, n) - dim!(g, hz, m, (n - 1)));
+        }
+    }
+
+    for m in 1..g.x_sz {
+        for n in 0..g.y_sz {
+            // ey(m, n) = ceye(m, n) * ey(m, n)
+            //  - ceyh(m, n) * (hz(m, n) - hz((m - 1), n))
+            dim!(g, ey, m, n) = dim!(g, ceye, m, n) * dim!(g, ey, m, n)
+                - dim!(g, ceyh, m, n) * (dim!(g, hz, m, n) - dim!(g, hz, (m - 1), n));
+        }
+    }
+}
+
 // 3D
 pub fn electric_3d(g: &mut Grid) {
     // ex(m, n, p) = cexe(m, n, p) * ex(m, n, p) + cexh(m, n, p)
@@ -148,3 +185,132 @@ pub fn electric_3d(g: &mut Grid) {
         }
     }
 }
+
+// Periodic boundaries. The updates above skip the nodes whose stencil
+// reaches beyond the grid: the last node along an axis for the magnetic
+// field, the first for the electric field. Along periodic axes the stencil
+// wraps around instead, so these nodes are updated afterwards.
+
+/// Update the magnetic nodes skipped by the regular update whose stencil
+/// wraps around the periodic axes.
+pub fn magnetic_periodic(g: &mut Grid, periodic: [bool; 3]) {
+    update_periodic(g, periodic, false);
+}
+
+/// Update the electric nodes skipped by the regular update whose stencil
+/// wraps around the periodic axes.
+pub fn electric_periodic(g: &mut Grid, periodic: [bool; 3]) {
+    update_periodic(g, periodic, true);
+}
+
+fn update_periodic(g: &mut Grid, periodic: [bool; 3], electric: bool) {
+    let updates: Vec<(Field, [usize; 3], f64)> = wrapped_nodes(g, periodic, electric)
+        .into_iter()
+        .map(|(f, pos)| {
+            (
+                f,
+                pos,
+                wrapped_update(g, f, pos, |f, p, _, _| g.field(f)[g.index(p)]),
+            )
+        })
+        .collect();
+
+    for (f, pos, v) in updates {
+        let i = g.index(pos);
+        let (field, _) = g.field_mut(f);
+        field[i] = v;
+    }
+}
+
+/// Nodes skipped by the regular update whose stencil wraps around the
+/// periodic axes; the first for the electric field, else the magnetic.
+pub(crate) fn wrapped_nodes(
+    g: &Grid,
+    periodic: [bool; 3],
+    electric: bool,
+) -> Vec<(Field, [usize; 3])> {
+    let used = [true, g.y_sz > 0, g.z_sz > 0];
+    let sz = [g.x_sz, g.y_sz.max(1), g.z_sz.max(1)];
+
+    // Position of the skipped nodes along an axis.
+    let edge = |t: usize| if electric { 0 } else { sz[t] - 1 };
+
+    let fields = if electric {
+        [Field::Ex, Field::Ey, Field::Ez]
+    } else {
+        [Field::Hx, Field::Hy, Field::Hz]
+    };
+
+    let mut nodes = Vec::new();
+    for f in fields.into_iter().filter(|f| g.has(*f)) {
+        // Axes the update differentiates along.
+        let axes: Vec<usize> = (0..3).filter(|t| *t != f.axis() && used[*t]).collect();
+
+        for &t in axes.iter().filter(|t| periodic[**t]) {
+            let mut range = [0..sz[0], 0..sz[1], 0..sz[2]];
+            range[t] = edge(t)..edge(t) + 1;
+
+            for x in range[0].clone() {
+                for y in range[1].clone() {
+                    for z in range[2].clone() {
+                        let pos = [x, y, z];
+
+                        // Skip nodes whose stencil leaves the grid along a
+                        // bounded axis, and nodes already visited from the
+                        // face of an earlier axis.
+                        let skip = axes
+                            .iter()
+                            .any(|&a| pos[a] == edge(a) && (!periodic[a] || a < t));
+
+                        if !skip {
+                            nodes.push((f, pos));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    nodes
+}
+
+/// Update component 'f' at 'pos', wrapping its stencil around the grid.
+/// 'value(f, p, axis, d)' gives component 'f' at 'p', a neighbour reached
+/// along 'axis' which lies across the high (d = 1) or low (d = -1) face of
+/// the grid, or within it (d = 0).
+pub(crate) fn wrapped_update(
+    g: &Grid,
+    f: Field,
+    pos: [usize; 3],
+    value: impl Fn(Field, [usize; 3], usize, isize) -> f64,
+) -> f64 {
+    let sz = [g.x_sz, g.y_sz.max(1), g.z_sz.max(1)];
+    let c = f.axis();
+    let (a, b) = ((c + 1) % 3, (c + 2) % 3);
+
+    // Value of component 'f' one node along 'axis' in the direction 'd'.
+    let at = |f: Field, d: isize, axis: usize| {
+        if !g.has(f) {
+            return 0.0;
+        }
+
+        let mut p = pos;
+        let q = p[axis] as isize + d;
+        p[axis] = q.rem_euclid(sz[axis] as isize) as usize;
+        value(f, p, axis, q.div_euclid(sz[axis] as isize))
+    };
+
+    let i = g.index(pos);
+    let (own, curl) = g.coefficients(f);
+    if f.is_electric() {
+        // e(c) = cece * e(c) + cech * ((h(b) - h(b)[-a]) - (h(a) - h(a)[-b]))
+        let (ha, hb) = (Field::magnetic(a), Field::magnetic(b));
+        own[i] * g.field(f)[i]
+            + curl[i] * ((at(hb, 0, a) - at(hb, -1, a)) - (at(ha, 0, b) - at(ha, -1, b)))
+    } else {
+        // h(c) = chch * h(c) - chce * ((e(b)[+a] - e(b)) - (e(a)[+b] - e(a)))
+        let (ea, eb) = (Field::electric(a), Field::electric(b));
+        own[i] * g.field(f)[i]
+            - curl[i] * ((at(eb, 1, a) - at(eb, 0, a)) - (at(ea, 1, b) - at(ea, 0, b)))
+    }
+}
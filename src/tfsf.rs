@@ -0,0 +1,418 @@
+// src/tfsf.rs
+//! Total-field/scattered-field (TFSF) boundaries; see chapters 3.10 and 8.5
+//! of Schneider.
+use crate::abc;
+use crate::error::FDTDError;
+use crate::grid::{Grid, IMP0};
+use crate::material::Material;
+use crate::step;
+use crate::waveform::Waveform;
+use std::cell::RefCell;
+
+pub use crate::grid::Axis;
+
+/// A 1D TFSF boundary introducing a wave travelling in the +x direction.
+/// Nodes at and beyond 'boundary' hold the total field; the incident wave
+/// is described by its electric field at the boundary node, 'waveform'.
+pub struct Tfsf1d<W>
+where
+    W: Waveform,
+{
+    boundary: usize,
+    waveform: W,
+
+    // Characteristic impedance and refractive index of the medium about
+    // the boundary.
+    impedance: f64,
+    index: f64,
+}
+
+impl<W> Tfsf1d<W>
+where
+    W: Waveform,
+{
+    /// Build a TFSF boundary at 'boundary', within a lossless 'medium'. The
+    /// boundary needs a scattered-field node before it, so this fails with
+    /// 'FDTDError::Region' on the first node.
+    pub fn new(boundary: usize, waveform: W, medium: Material) -> Result<Self, FDTDError> {
+        if boundary == 0 {
+            return Err(FDTDError::Region {
+                min: [0; 3],
+                max: [0; 3],
+                reason: "leaves no scattered-field node",
+            });
+        }
+
+        Ok(Tfsf1d {
+            boundary,
+            waveform,
+            impedance: IMP0 * (medium.mu_r / medium.eps_r).sqrt(),
+            index: (medium.eps_r * medium.mu_r).sqrt(),
+        })
+    }
+
+    /// Correct the scattered-field Hy node to the left of the boundary;
+    /// called after the magnetic update of time step 't'.
+    pub fn magnetic(&self, t: usize, g: &mut Grid) {
+        let b = self.boundary;
+        g.hy[b - 1] -= g.chye[b - 1] * self.waveform.step(t);
+    }
+
+    /// Correct the total-field Ez node on the boundary; called after the
+    /// electric update of time step 't'.
+    pub fn electric(&self, t: usize, g: &mut Grid) {
+        // The incident Hy sits half a time step later and half a cell
+        // behind the boundary; the wave takes 0.5 * n / cdtds time steps to
+        // cover the half cell.
+        let time = t as f64 + 0.5 + 0.5 * self.index / g.cdtds;
+
+        let b = self.boundary;
+        g.ez[b] += g.cezh[b] * self.waveform.value(time) / self.impedance;
+    }
+}
+
+type AbcFn = Box<dyn FnMut(usize, &mut Grid)>;
+
+// Auxiliary 1D grid holding the incident field of a 2D or 3D TFSF
+// boundary.
+struct Aux {
+    g: Grid,
+    abc: AbcFn,
+}
+
+impl Aux {
+    // The auxiliary grid must share the Courant number of the grid it
+    // feeds for the incident field to line up.
+    fn new(len: usize, cdtds: f64) -> Self {
+        let mut g = Grid::new_1d(len);
+        g.cdtds = cdtds;
+        g.cezh = vec![cdtds * IMP0; len];
+        g.chye = vec![cdtds / IMP0; len];
+
+        let abc = abc::advection_abc_2nd_order(&g.cezh, &g.chye);
+        Aux {
+            g,
+            abc: Box::new(abc),
+        }
+    }
+
+    // Advance the incident field to time step 't + 1', driving the first
+    // node with 'waveform'.
+    fn step(&mut self, t: usize, waveform: &impl Waveform) {
+        step::magnetic_1d(&mut self.g);
+        step::electric_1d(&mut self.g);
+        (self.abc)(t, &mut self.g);
+        self.g.ez[0] = waveform.step(t + 1);
+    }
+}
+
+/// A TFSF boundary for a 2D TM^z grid, introducing a plane wave travelling
+/// in the +x direction. Ez nodes within the box spanned by 'first' and
+/// 'last' (inclusive) hold the total field.
+///
+/// The incident field is computed by an auxiliary 1D grid stepped alongside
+/// the 2D grid, so it suffers the same numeric dispersion. Node 'm' of the
+/// auxiliary grid lines up with column 'm' of the 2D grid; 'waveform'
+/// drives its first node.
+pub struct Tfsf2d<W>
+where
+    W: Waveform,
+{
+    first: (usize, usize),
+    last: (usize, usize),
+    waveform: W,
+
+    // Stepped from 'magnetic', which only borrows self so that both
+    // corrections may be captured by the post-{magnetic/electric} closures.
+    aux: RefCell<Aux>,
+}
+
+impl<W> Tfsf2d<W>
+where
+    W: Waveform,
+{
+    /// Build a TFSF boundary for 'g'; the box must lie within free space.
+    /// If it's empty or touches the edges of the grid, this fails with
+    /// 'FDTDError::Region'.
+    pub fn new(
+        g: &Grid,
+        first: (usize, usize),
+        last: (usize, usize),
+        waveform: W,
+    ) -> Result<Self, FDTDError> {
+        let error = |reason| {
+            Err(FDTDError::Region {
+                min: [first.0, first.1, 0],
+                max: [last.0, last.1, 0],
+                reason,
+            })
+        };
+        if first.0 == 0 || first.1 == 0 || last.0 + 1 >= g.x_sz || last.1 + 1 >= g.y_sz {
+            return error("touches the edge of the grid");
+        }
+        if first.0 > last.0 || first.1 > last.1 {
+            return error("is empty");
+        }
+
+        Ok(Tfsf2d {
+            first,
+            last,
+            waveform,
+            aux: RefCell::new(Aux::new(g.x_sz, g.cdtds)),
+        })
+    }
+
+    /// Correct the scattered-field Hx and Hy nodes bordering the box, then
+    /// advance the auxiliary grid; called after the magnetic update of time
+    /// step 't'.
+    pub fn magnetic(&self, t: usize, g: &mut Grid) {
+        let mut aux = self.aux.borrow_mut();
+        let ez1 = &aux.g.ez;
+        let ((x0, y0), (x1, y1)) = (self.first, self.last);
+        let y_sz = g.y_sz;
+
+        for n in y0..=y1 {
+            // Left and right.
+            let i = (x0 - 1) * y_sz + n;
+            g.hy[i] -= g.chye[i] * ez1[x0];
+
+            let i = x1 * y_sz + n;
+            g.hy[i] += g.chye[i] * ez1[x1];
+        }
+
+        for (m, inc) in ez1.iter().enumerate().take(x1 + 1).skip(x0) {
+            // Bottom and top.
+            let i = m * y_sz + y0 - 1;
+            g.hx[i] += g.chxe[i] * inc;
+
+            let i = m * y_sz + y1;
+            g.hx[i] -= g.chxe[i] * inc;
+        }
+
+        aux.step(t, &self.waveform);
+    }
+
+    /// Correct the total-field Ez nodes on the left and right of the box;
+    /// called after the electric update of time step 't'. The incident wave
+    /// has no Hx, so the top and bottom need no correction.
+    pub fn electric(&self, _t: usize, g: &mut Grid) {
+        let aux = self.aux.borrow();
+        let hy1 = &aux.g.hy;
+        let ((x0, y0), (x1, y1)) = (self.first, self.last);
+        let y_sz = g.y_sz;
+
+        for n in y0..=y1 {
+            let i = x0 * y_sz + n;
+            g.ez[i] -= g.cezh[i] * hy1[x0 - 1];
+
+            let i = x1 * y_sz + n;
+            g.ez[i] += g.cezh[i] * hy1[x1];
+        }
+    }
+}
+
+/// A TFSF boundary for a 3D grid, introducing a plane wave travelling in
+/// the positive direction of 'propagation' with its electric field along
+/// 'polarization'. Nodes within the box spanned by 'first' and 'last'
+/// (inclusive) hold the total field.
+///
+/// As in 2D, the incident field is computed by an auxiliary 1D grid whose
+/// node 'm' lines up with index 'm' along 'propagation'; 'waveform'
+/// drives its first node.
+pub struct Tfsf3d<W>
+where
+    W: Waveform,
+{
+    first: [usize; 3],
+    last: [usize; 3],
+    propagation: usize,
+    polarization: usize,
+    waveform: W,
+
+    // The auxiliary grid's Hy holds the incident magnetic field with this
+    // sign.
+    sign: f64,
+
+    aux: RefCell<Aux>,
+}
+
+// Field vectors of a grid by axis.
+fn e_mut(g: &mut Grid, axis: usize) -> (&mut Vec<f64>, &Vec<f64>) {
+    match axis {
+        0 => (&mut g.ex, &g.cexh),
+        1 => (&mut g.ey, &g.ceyh),
+        _ => (&mut g.ez, &g.cezh),
+    }
+}
+
+fn h_mut(g: &mut Grid, axis: usize) -> (&mut Vec<f64>, &Vec<f64>) {
+    match axis {
+        0 => (&mut g.hx, &g.chxe),
+        1 => (&mut g.hy, &g.chye),
+        _ => (&mut g.hz, &g.chze),
+    }
+}
+
+impl<W> Tfsf3d<W>
+where
+    W: Waveform,
+{
+    /// Build a TFSF boundary for 'g'; the box must lie within free space.
+    /// If it's empty or touches the edges of the grid, this fails with
+    /// 'FDTDError::Region', and if the wave is polarized along its
+    /// direction of propagation, with 'FDTDError::Polarization'.
+    pub fn new(
+        g: &Grid,
+        first: [usize; 3],
+        last: [usize; 3],
+        propagation: Axis,
+        polarization: Axis,
+        waveform: W,
+    ) -> Result<Self, FDTDError> {
+        let error = |reason| {
+            Err(FDTDError::Region {
+                min: first,
+                max: last,
+                reason,
+            })
+        };
+        let sz = [g.x_sz, g.y_sz, g.z_sz];
+        if (0..3).any(|a| first[a] == 0 || last[a] + 1 >= sz[a]) {
+            return error("touches the edge of the grid");
+        }
+        if (0..3).any(|a| first[a] > last[a]) {
+            return error("is empty");
+        }
+        if propagation == polarization {
+            return Err(FDTDError::Polarization { axis: propagation });
+        }
+
+        let (p, e) = (propagation.index(), polarization.index());
+
+        // The 1D update is that of Ez and Hy for a wave along x. Relabeling
+        // axes cyclically keeps the curl intact when the electric field
+        // follows the direction of propagation as z follows x; otherwise the
+        // magnetic field flips.
+        let sign = if e == (p + 2) % 3 { 1.0 } else { -1.0 };
+
+        Ok(Tfsf3d {
+            first,
+            last,
+            propagation: p,
+            polarization: e,
+            waveform,
+            sign,
+            aux: RefCell::new(Aux::new(sz[p], g.cdtds)),
+        })
+    }
+
+    // Call 'f(a, b, c, hi)' for each face of the box, with 'a' the normal
+    // and 'b' and 'c' the tangential axes, in both orders, of the face.
+    fn faces(&self, mut f: impl FnMut(usize, usize, usize, bool)) {
+        for a in 0..3 {
+            for (b, c) in [((a + 1) % 3, (a + 2) % 3), ((a + 2) % 3, (a + 1) % 3)] {
+                f(a, b, c, false);
+                f(a, b, c, true);
+            }
+        }
+    }
+
+    // Call 'f(index, pos)' for each tangential node on a face, where 'pos'
+    // holds the grid indices of the node; nodes sit half a cell along 'c'
+    // and, for the magnetic field, just outside the box along 'a'.
+    fn nodes(
+        &self,
+        g: &Grid,
+        (a, b, c): (usize, usize, usize),
+        pos_a: usize,
+        mut f: impl FnMut(usize, [usize; 3]),
+    ) {
+        for j in self.first[b]..=self.last[b] {
+            for k in self.first[c]..self.last[c] {
+                let mut pos = [0; 3];
+                pos[a] = pos_a;
+                pos[b] = j;
+                pos[c] = k;
+                f(g.index(pos), pos);
+            }
+        }
+    }
+
+    /// Correct the scattered-field magnetic nodes bordering the box, then
+    /// advance the auxiliary grid; called after the magnetic update of time
+    /// step 't'.
+    pub fn magnetic(&self, t: usize, g: &mut Grid) {
+        let mut aux = self.aux.borrow_mut();
+        let ez1 = &aux.g.ez;
+        let (p, e) = (self.propagation, self.polarization);
+
+        // Hb is updated from Ec through 'sign * d/da Ec'.
+        self.faces(|a, b, c, hi| {
+            if c != e {
+                return;
+            }
+
+            let sign = if a == (b + 2) % 3 { 1.0 } else { -1.0 };
+            let (pos_a, edge) = if hi {
+                (self.last[a], self.last[a])
+            } else {
+                (self.first[a] - 1, self.first[a])
+            };
+
+            let mut corrections = Vec::new();
+            self.nodes(g, (a, b, c), pos_a, |i, pos| {
+                let inc = ez1[if p == a { edge } else { pos[p] }];
+                corrections.push((i, inc));
+            });
+
+            let (h, che) = h_mut(g, b);
+            for (i, inc) in corrections {
+                if hi {
+                    h[i] += sign * che[i] * inc;
+                } else {
+                    h[i] -= sign * che[i] * inc;
+                }
+            }
+        });
+
+        aux.step(t, &self.waveform);
+    }
+
+    /// Correct the total-field electric nodes on the faces of the box;
+    /// called after the electric update of time step 't'.
+    pub fn electric(&self, _t: usize, g: &mut Grid) {
+        let aux = self.aux.borrow();
+        let hy1 = &aux.g.hy;
+        let (p, e) = (self.propagation, self.polarization);
+        let h = 3 - p - e;
+
+        // Ec is updated from Hb through 'sign * d/da Hb'.
+        self.faces(|a, b, c, hi| {
+            if b != h {
+                return;
+            }
+
+            let sign = if a == (c + 1) % 3 { 1.0 } else { -1.0 };
+            let (pos_a, edge) = if hi {
+                (self.last[a], self.last[a])
+            } else {
+                (self.first[a], self.first[a] - 1)
+            };
+
+            let mut corrections = Vec::new();
+            self.nodes(g, (a, b, c), pos_a, |i, pos| {
+                let inc = self.sign * hy1[if p == a { edge } else { pos[p] }];
+                corrections.push((i, inc));
+            });
+
+            let (f, ceh) = e_mut(g, c);
+            for (i, inc) in corrections {
+                if hi {
+                    f[i] += sign * ceh[i] * inc;
+                } else {
+                    f[i] -= sign * ceh[i] * inc;
+                }
+            }
+        });
+    }
+}
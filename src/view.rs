@@ -0,0 +1,196 @@
+// src/view.rs
+//! Bounds-checked, multi-dimensional views of the field components of a
+//! grid, sharing one definition of their memory layout.
+//!
+//! Components are stored x-major: the node at '[x, y, z]' lies at
+//! '(x * y_sz + y) * z_sz + z', with each axis the grid doesn't have taken
+//! as one node long. Nodes along z are contiguous, as are the nodes at each
+//! x.
+use std::ops::{Index, IndexMut};
+
+/// Offset of the node at '[x, y, z]' within a component of 'shape', which
+/// must lie within it.
+macro_rules! layout {
+    ($shape:expr, $x:expr, $y:expr, $z:expr) => {
+        ($x * $shape[1] + $y) * $shape[2] + $z
+    };
+}
+pub(crate) use layout;
+
+/// Offset of the node at 'pos' within a component of 'shape', if it lies
+/// within it.
+pub(crate) fn offset(shape: [usize; 3], pos: [usize; 3]) -> Option<usize> {
+    if pos.iter().zip(shape).all(|(p, s)| *p < s) {
+        Some(layout!(shape, pos[0], pos[1], pos[2]))
+    } else {
+        None
+    }
+}
+
+/// Offset of the node at 'pos' within a component of 'shape'; panics if it
+/// lies outside of it.
+pub(crate) fn index(shape: [usize; 3], pos: [usize; 3]) -> usize {
+    match offset(shape, pos) {
+        Some(i) => i,
+        None => out_of_bounds(shape, pos),
+    }
+}
+
+/// Position of the node at offset 'i' within a component of 'shape'.
+pub(crate) fn position(shape: [usize; 3], i: usize) -> [usize; 3] {
+    let [_, y_sz, z_sz] = shape;
+    [i / (y_sz * z_sz), i / z_sz % y_sz, i % z_sz]
+}
+
+/// Positions of the nodes of 'shape', in the order they're stored in.
+pub fn positions(shape: [usize; 3]) -> impl Iterator<Item = [usize; 3]> {
+    (0..shape.iter().product()).map(move |i| position(shape, i))
+}
+
+/// Panic for a position outside of 'shape'.
+fn out_of_bounds(shape: [usize; 3], pos: [usize; 3]) -> ! {
+    panic!("Position {:?} is out of bounds for shape {:?}", pos, shape)
+}
+
+/// A view of a field component.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldView<'a> {
+    shape: [usize; 3],
+    data: &'a [f64],
+}
+
+impl<'a> FieldView<'a> {
+    pub(crate) fn new(shape: [usize; 3], data: &'a [f64]) -> Self {
+        FieldView { shape, data }
+    }
+
+    /// Number of nodes along each axis, one for axes the grid doesn't have.
+    pub fn shape(&self) -> [usize; 3] {
+        self.shape
+    }
+
+    /// Value at 'pos', if it lies within the grid.
+    pub fn get(&self, pos: [usize; 3]) -> Option<f64> {
+        offset(self.shape, pos).map(|i| self.data[i])
+    }
+
+    /// Nodes at 'x': a single node on 1D grids, the nodes along y on 2D
+    /// grids and the yz-plane, z-major, on 3D grids.
+    pub fn plane(&self, x: usize) -> &'a [f64] {
+        let len = self.shape[1] * self.shape[2];
+        match offset(self.shape, [x, 0, 0]) {
+            Some(i) => &self.data[i..i + len],
+            None => out_of_bounds(self.shape, [x, 0, 0]),
+        }
+    }
+
+    /// Nodes at 'x' and 'y' along z; a single node on 1D and 2D grids.
+    pub fn row(&self, x: usize, y: usize) -> &'a [f64] {
+        match offset(self.shape, [x, y, 0]) {
+            Some(i) => &self.data[i..i + self.shape[2]],
+            None => out_of_bounds(self.shape, [x, y, 0]),
+        }
+    }
+
+    /// Every node with its position, in the order they're stored in.
+    pub fn iter(&self) -> impl Iterator<Item = ([usize; 3], f64)> + 'a {
+        positions(self.shape).zip(self.data.iter().copied())
+    }
+
+    /// The nodes as stored.
+    pub fn as_slice(&self) -> &'a [f64] {
+        self.data
+    }
+}
+
+impl Index<[usize; 3]> for FieldView<'_> {
+    type Output = f64;
+
+    fn index(&self, pos: [usize; 3]) -> &f64 {
+        match offset(self.shape, pos) {
+            Some(i) => &self.data[i],
+            None => out_of_bounds(self.shape, pos),
+        }
+    }
+}
+
+/// A mutable view of a field component.
+#[derive(Debug)]
+pub struct FieldViewMut<'a> {
+    shape: [usize; 3],
+    data: &'a mut [f64],
+}
+
+impl<'a> FieldViewMut<'a> {
+    pub(crate) fn new(shape: [usize; 3], data: &'a mut [f64]) -> Self {
+        FieldViewMut { shape, data }
+    }
+
+    /// Number of nodes along each axis, one for axes the grid doesn't have.
+    pub fn shape(&self) -> [usize; 3] {
+        self.shape
+    }
+
+    /// Value at 'pos', if it lies within the grid.
+    pub fn get(&self, pos: [usize; 3]) -> Option<f64> {
+        offset(self.shape, pos).map(|i| self.data[i])
+    }
+
+    /// Node at 'pos', if it lies within the grid.
+    pub fn get_mut(&mut self, pos: [usize; 3]) -> Option<&mut f64> {
+        offset(self.shape, pos).map(|i| &mut self.data[i])
+    }
+
+    /// Node at 'pos', if it lies within the grid, borrowed for as long as
+    /// the view was.
+    pub fn into_mut(self, pos: [usize; 3]) -> Option<&'a mut f64> {
+        offset(self.shape, pos).map(|i| &mut self.data[i])
+    }
+
+    /// Nodes at 'x'; see 'FieldView::plane'.
+    pub fn plane_mut(&mut self, x: usize) -> &mut [f64] {
+        let len = self.shape[1] * self.shape[2];
+        match offset(self.shape, [x, 0, 0]) {
+            Some(i) => &mut self.data[i..i + len],
+            None => out_of_bounds(self.shape, [x, 0, 0]),
+        }
+    }
+
+    /// Nodes at 'x' and 'y' along z; see 'FieldView::row'.
+    pub fn row_mut(&mut self, x: usize, y: usize) -> &mut [f64] {
+        match offset(self.shape, [x, y, 0]) {
+            Some(i) => &mut self.data[i..i + self.shape[2]],
+            None => out_of_bounds(self.shape, [x, y, 0]),
+        }
+    }
+
+    /// Every node with its position, in the order they're stored in.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ([usize; 3], &mut f64)> {
+        positions(self.shape).zip(self.data.iter_mut())
+    }
+
+    /// The nodes as stored.
+    pub fn as_mut_slice(&mut self) -> &mut [f64] {
+        self.data
+    }
+}
+
+impl Index<[usize; 3]> for FieldViewMut<'_> {
+    type Output = f64;
+
+    fn index(&self, pos: [usize; 3]) -> &f64 {
+        match offset(self.shape, pos) {
+            Some(i) => &self.data[i],
+            None => out_of_bounds(self.shape, pos),
+        }
+    }
+}
+
+impl IndexMut<[usize; 3]> for FieldViewMut<'_> {
+    fn index_mut(&mut self, pos: [usize; 3]) -> &mut f64 {
+        match offset(self.shape, pos) {
+            Some(i) => &mut self.data[i],
+            None => out_of_bounds(self.shape, pos),
+        }
+    }
+}
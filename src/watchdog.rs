@@ -0,0 +1,88 @@
+// src/watchdog.rs
+//! A watchdog which halts a simulation once its fields turn unstable,
+//! rather than letting it carry on with NaN.
+use crate::error::FDTDError;
+use crate::grid::{Field, Grid};
+
+/// Checks the fields every 'interval' steps for values which aren't finite
+/// and, optionally, for peaks growing exponentially.
+#[derive(Debug, Clone)]
+pub struct Watchdog {
+    interval: usize,
+
+    // Factor a component's peak must grow by over each of a number of
+    // checks in a row to count as unstable.
+    growth: Option<(f64, usize)>,
+
+    // Peak magnitude of each component at the last check, and the number of
+    // checks in a row it has grown by the factor over.
+    peaks: [f64; 6],
+    streaks: [usize; 6],
+}
+
+impl Watchdog {
+    /// Check for values which aren't finite every 'interval' steps.
+    pub fn new(interval: usize) -> Self {
+        assert!(interval > 0, "Watchdog interval must be at least one step");
+
+        Watchdog {
+            interval,
+            growth: None,
+            peaks: [0.0; 6],
+            streaks: [0; 6],
+        }
+    }
+
+    /// Also halt once the peak magnitude of a component has grown by more
+    /// than 'factor' over each of 'checks' checks in a row. Growth from
+    /// zero isn't counted, so sources switching on aren't mistaken for it.
+    pub fn with_growth(mut self, factor: f64, checks: usize) -> Self {
+        assert!(factor > 1.0 && checks > 0, "Invalid watchdog growth");
+        self.growth = Some((factor, checks));
+        self
+    }
+
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// Check the fields of 'g' once 'step' steps have been taken, naming the
+    /// first value which isn't finite or the peak which has grown.
+    pub(crate) fn check(&mut self, step: usize, g: &Grid) -> Result<(), FDTDError> {
+        for (c, f) in Field::ALL.into_iter().enumerate() {
+            let v = g.field(f);
+            let unstable = |i: usize| FDTDError::Unstable {
+                step,
+                field: f,
+                pos: g.position(i),
+                value: v[i],
+            };
+
+            if let Some(i) = v.iter().position(|v| !v.is_finite()) {
+                return Err(unstable(i));
+            }
+
+            if let Some((factor, checks)) = self.growth {
+                let (i, peak) = v
+                    .iter()
+                    .map(|v| v.abs())
+                    .enumerate()
+                    .fold((0, 0.0), |a, b| if b.1 > a.1 { b } else { a });
+
+                let last = self.peaks[c];
+                self.peaks[c] = peak;
+                if last > 0.0 && peak > factor * last {
+                    self.streaks[c] += 1;
+                } else {
+                    self.streaks[c] = 0;
+                }
+
+                if self.streaks[c] >= checks {
+                    return Err(unstable(i));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
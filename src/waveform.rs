@@ -0,0 +1,186 @@
+// src/waveform.rs
+//! Source waveforms, evaluated at (possibly fractional) time steps.
+use crate::ricker;
+use std::f64::consts::PI;
+
+/// A source waveform.
+pub trait Waveform {
+    /// Value of the waveform at 'time', measured in time steps.
+    fn value(&self, time: f64) -> f64;
+
+    /// Value of the waveform at time step 'q'.
+    fn step(&self, q: usize) -> f64 {
+        self.value(q as f64)
+    }
+
+    /// Value of the waveform half a time step after time step 'q'; magnetic
+    /// fields live on the half steps.
+    fn half_step(&self, q: usize) -> f64 {
+        self.value(q as f64 + 0.5)
+    }
+}
+
+/// Any closure of the time may be used as a waveform.
+impl<F> Waveform for F
+where
+    F: Fn(f64) -> f64,
+{
+    fn value(&self, time: f64) -> f64 {
+        self(time)
+    }
+}
+
+/// Ricker wavelet; see `ricker::ricker`.
+#[derive(Debug, Clone, Copy)]
+pub struct Ricker {
+    cdtds: f64,
+    ppw: f64,
+    location: f64,
+}
+
+impl Ricker {
+    /// Ricker wavelet with 'ppw' points per wavelength at its peak
+    /// frequency, as seen at 'location' cells from the source.
+    pub fn new(cdtds: f64, ppw: f64, location: f64) -> Self {
+        Ricker {
+            cdtds,
+            ppw,
+            location,
+        }
+    }
+}
+
+impl Waveform for Ricker {
+    fn value(&self, time: f64) -> f64 {
+        ricker::ricker(time, self.location, self.cdtds, self.ppw)
+    }
+}
+
+/// Gaussian pulse, exp(-((t - delay) / width)^2).
+#[derive(Debug, Clone, Copy)]
+pub struct Gaussian {
+    delay: f64,
+    width: f64,
+}
+
+impl Gaussian {
+    pub fn new(delay: f64, width: f64) -> Self {
+        Gaussian { delay, width }
+    }
+
+    fn arg(&self, time: f64) -> f64 {
+        (time - self.delay) / self.width
+    }
+}
+
+impl Waveform for Gaussian {
+    fn value(&self, time: f64) -> f64 {
+        let arg = self.arg(time);
+        (-arg * arg).exp()
+    }
+}
+
+/// Differentiated Gaussian pulse, scaled to a peak of one. Unlike the
+/// Gaussian, it carries no DC component.
+#[derive(Debug, Clone, Copy)]
+pub struct DiffGaussian {
+    gaussian: Gaussian,
+}
+
+impl DiffGaussian {
+    pub fn new(delay: f64, width: f64) -> Self {
+        DiffGaussian {
+            gaussian: Gaussian::new(delay, width),
+        }
+    }
+}
+
+impl Waveform for DiffGaussian {
+    fn value(&self, time: f64) -> f64 {
+        // The derivative of exp(-x^2) peaks at x = -1 / sqrt(2).
+        let arg = self.gaussian.arg(time);
+        -(2.0 * std::f64::consts::E).sqrt() * arg * (-arg * arg).exp()
+    }
+}
+
+/// Gaussian pulse modulating a sinusoid, with 'frequency' in cycles per time
+/// step.
+#[derive(Debug, Clone, Copy)]
+pub struct ModulatedGaussian {
+    gaussian: Gaussian,
+    frequency: f64,
+}
+
+impl ModulatedGaussian {
+    pub fn new(delay: f64, width: f64, frequency: f64) -> Self {
+        ModulatedGaussian {
+            gaussian: Gaussian::new(delay, width),
+            frequency,
+        }
+    }
+}
+
+impl Waveform for ModulatedGaussian {
+    fn value(&self, time: f64) -> f64 {
+        let phase = 2.0 * PI * self.frequency * (time - self.gaussian.delay);
+        self.gaussian.value(time) * phase.sin()
+    }
+}
+
+/// Continuous sinusoid, with 'frequency' in cycles per time step. It's
+/// brought up over 'ramp' time steps with a raised cosine, to keep the
+/// turn-on from exciting frequencies far from 'frequency'.
+#[derive(Debug, Clone, Copy)]
+pub struct Sinusoid {
+    frequency: f64,
+    ramp: f64,
+}
+
+impl Sinusoid {
+    pub fn new(frequency: f64, ramp: f64) -> Self {
+        Sinusoid { frequency, ramp }
+    }
+}
+
+impl Waveform for Sinusoid {
+    fn value(&self, time: f64) -> f64 {
+        let envelope = if time <= 0.0 {
+            0.0
+        } else if time < self.ramp {
+            0.5 * (1.0 - (PI * time / self.ramp).cos())
+        } else {
+            1.0
+        };
+
+        envelope * (2.0 * PI * self.frequency * time).sin()
+    }
+}
+
+/// User supplied samples, one per time step. Values between steps are
+/// linearly interpolated; the waveform is zero outside of the samples.
+#[derive(Debug, Clone)]
+pub struct Sampled {
+    data: Vec<f64>,
+}
+
+impl Sampled {
+    pub fn new(data: Vec<f64>) -> Self {
+        Sampled { data }
+    }
+}
+
+impl Waveform for Sampled {
+    fn value(&self, time: f64) -> f64 {
+        if time < 0.0 {
+            return 0.0;
+        }
+
+        let q = time.floor() as usize;
+        let frac = time - q as f64;
+        match (self.data.get(q), self.data.get(q + 1)) {
+            (Some(v), Some(next)) => v * (1.0 - frac) + next * frac,
+            (Some(v), None) if frac == 0.0 => *v,
+            _ => 0.0,
+        }
+    }
+}
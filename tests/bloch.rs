@@ -0,0 +1,181 @@
+// tests/bloch.rs
+/// Check Bloch-periodic boundaries.
+use fdtd::bloch::{self, Bloch};
+use fdtd::cpml::{Cpml, CpmlParams};
+use fdtd::dispersion::Dispersion;
+use fdtd::error::FDTDError;
+use fdtd::fdtd::{GridDimension, Polarization};
+use fdtd::grid::{Field, Grid};
+use fdtd::sources::PointSource;
+use fdtd::waveform::{DiffGaussian, Gaussian, Waveform};
+use std::f64::consts::PI;
+
+mod util;
+use util::sim::sim;
+
+/// A waveform with the opposite sign.
+struct Negated<W: Waveform>(W);
+
+impl<W: Waveform> Waveform for Negated<W> {
+    fn value(&self, time: f64) -> f64 {
+        -self.0.value(time)
+    }
+}
+
+/// With no phase, Bloch-periodic boundaries are plain periodic ones and the
+/// imaginary part is never excited.
+#[test]
+fn test_zero_phase() {
+    let (x_sz, y_sz) = (30, 20);
+    let source = || PointSource::soft(Field::Ez, [8, 5, 0], Gaussian::new(15.0, 5.0));
+
+    let mut g = Grid::new_2d(x_sz, y_sz, None);
+    let mut s = sim(GridDimension::Two(Polarization::Magnetic));
+    s.set_bloch(Some(Bloch::new(&g, [Some(0.0), Some(0.0), None])))
+        .unwrap();
+    s.add_source(source());
+    s.step_mul(&mut g, 70).unwrap();
+
+    let mut periodic = Grid::new_2d(x_sz, y_sz, None);
+    let mut s_periodic = sim(GridDimension::Two(Polarization::Magnetic));
+    s_periodic.set_periodic([true, true, false]);
+    s_periodic.add_source(source());
+    s_periodic.step_mul(&mut periodic, 70).unwrap();
+
+    assert_eq!(g.ez, periodic.ez);
+    assert_eq!(g.hx, periodic.hx);
+    assert_eq!(g.hy, periodic.hy);
+
+    let imag = s.bloch().unwrap().imag();
+    assert!(imag.ez.iter().chain(&imag.hx).all(|v| *v == 0.0));
+}
+
+/// A phase of π flips the sign of the fields across the grid, which matches
+/// a periodic grid twice as long excited by a source and its negative half
+/// the grid apart.
+#[test]
+fn test_antiperiodic() {
+    let (x_sz, y_sz) = (24, 16);
+    let pulse = || Gaussian::new(15.0, 5.0);
+
+    let mut g = Grid::new_2d_te(x_sz, y_sz, None);
+    let mut s = sim(GridDimension::Two(Polarization::Electric));
+    s.set_bloch(Some(Bloch::new(&g, [Some(PI), None, None])))
+        .unwrap();
+    s.add_source(PointSource::soft(Field::Hz, [6, 8, 0], pulse()));
+    s.step_mul(&mut g, 60).unwrap();
+
+    let mut double = Grid::new_2d_te(2 * x_sz, y_sz, None);
+    let mut s_double = sim(GridDimension::Two(Polarization::Electric));
+    s_double.set_periodic([true, false, false]);
+    s_double.add_source(PointSource::soft(Field::Hz, [6, 8, 0], pulse()));
+    s_double.add_source(PointSource::soft(
+        Field::Hz,
+        [6 + x_sz, 8, 0],
+        Negated(pulse()),
+    ));
+    s_double.step_mul(&mut double, 60).unwrap();
+
+    assert!(g.hz.iter().any(|v| v.abs() > 0.01));
+    for i in 0..x_sz * y_sz {
+        assert!((g.hz[i] - double.hz[i]).abs() < 1e-9);
+        assert!((g.ex[i] - double.ex[i]).abs() < 1e-9);
+        assert!((g.ey[i] - double.ey[i]).abs() < 1e-9);
+    }
+}
+
+/// At the magic time step a 1D grid has no numerical dispersion, so the
+/// bands at phase 'φ' lie at 'ω = |φ + 2πn| / L'.
+#[test]
+fn test_bands_1d() {
+    let (len, phase) = (20, 1.0);
+    let mut g = Grid::new_1d(len);
+    let mut s = sim(GridDimension::One);
+    s.set_bloch(Some(Bloch::with_wavevector(
+        &g,
+        [phase / len as f64, 0.0, 0.0],
+        [true, false, false],
+    )))
+    .unwrap();
+    s.add_source(PointSource::soft(
+        Field::Ez,
+        [3, 0, 0],
+        DiffGaussian::new(12.0, 2.0),
+    ));
+
+    let (mut re, mut im) = (Vec::new(), Vec::new());
+    for _ in 0..4000 {
+        s.step(&mut g).unwrap();
+        re.push(g.ez[11]);
+        im.push(s.bloch().unwrap().imag().ez[11]);
+    }
+
+    let omegas: Vec<f64> = (1..=500).map(|i| i as f64 * 0.001).collect();
+    let found = bloch::resonances(&re, &im, &omegas);
+    let expected: Vec<f64> = [0.0, -1.0, 1.0]
+        .iter()
+        .map(|n| (phase + 2.0 * PI * n).abs() / len as f64)
+        .collect();
+
+    assert_eq!(found.len(), expected.len(), "{:?}", found);
+    for (f, e) in found.iter().zip(&expected) {
+        assert!((f - e).abs() < 2e-3, "{:?} {:?}", found, expected);
+    }
+}
+
+/// Bloch-periodic boundaries can't be combined with a CPML or dispersive
+/// media, in whichever order they're set.
+#[test]
+fn test_combination() {
+    let dimension = GridDimension::Two(Polarization::Magnetic);
+    let g = Grid::new_2d(30, 20, None);
+    let bloch = || Some(Bloch::new(&g, [Some(PI), None, None]));
+    let cpml = || Some(Cpml::new(&g, dimension, &CpmlParams::default()));
+
+    let mut s = sim(dimension);
+    s.set_bloch(bloch()).unwrap();
+    assert!(matches!(
+        s.set_cpml(cpml()),
+        Err(FDTDError::Combination { .. })
+    ));
+    assert!(matches!(
+        s.set_dispersion(Some(Dispersion::new())),
+        Err(FDTDError::Combination { .. })
+    ));
+
+    // Clearing either is always allowed.
+    s.set_cpml(None).unwrap();
+    s.set_bloch(None).unwrap();
+    s.set_cpml(cpml()).unwrap();
+    assert!(matches!(
+        s.set_bloch(bloch()),
+        Err(FDTDError::Combination { .. })
+    ));
+
+    let mut s = sim(dimension);
+    s.set_dispersion(Some(Dispersion::new())).unwrap();
+    assert!(matches!(
+        s.set_bloch(bloch()),
+        Err(FDTDError::Combination { .. })
+    ));
+}
+
+/// The imaginary part, built from one grid, isn't stepped alongside another.
+#[test]
+fn test_other_grid() {
+    let g = Grid::new_2d(20, 20, None);
+    let mut s = sim(GridDimension::Two(Polarization::Magnetic));
+    s.set_bloch(Some(Bloch::new(&g, [Some(1.0), None, None])))
+        .unwrap();
+
+    let mut g = Grid::new_2d(20, 10, None);
+    match s.step(&mut g) {
+        Err(FDTDError::Shape {
+            field,
+            len,
+            expected,
+            ..
+        }) => assert_eq!((field, len, expected), (Field::Hx, 400, 200)),
+        r => panic!("{:?}", r),
+    }
+}
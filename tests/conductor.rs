@@ -0,0 +1,187 @@
+// tests/conductor.rs
+/// Check perfect electric and magnetic conductors.
+use fdtd::conductor::{Conductor, Conductors};
+use fdtd::error::FDTDError;
+use fdtd::fdtd::{GridDimension, Polarization};
+use fdtd::geometry::{Circle, HalfSpace, Sphere};
+use fdtd::grid::{Field, Grid};
+use fdtd::sources::PointSource;
+use fdtd::waveform::Gaussian;
+
+mod util;
+use util::sim::sim;
+
+/// Return the largest incident and reflected fields seen at node 100 of a
+/// 1D grid whose right half is a perfect conductor, keeping their signs.
+fn reflect_1d(conductor: Conductor) -> (f64, f64) {
+    let mut g = Grid::new_1d(400);
+    let mut c = Conductors::new(&g);
+    c.add(
+        &g,
+        &HalfSpace::new([200.0, 0.0, 0.0], [1.0, 0.0, 0.0]),
+        conductor,
+    );
+
+    let mut s = sim(GridDimension::One);
+    s.set_conductors(Some(c));
+    s.add_source(PointSource::soft(
+        Field::Ez,
+        [150, 0, 0],
+        Gaussian::new(30.0, 8.0),
+    ));
+
+    let largest = |a: f64, b: f64| if b.abs() > a.abs() { b } else { a };
+    let (mut incident, mut reflected) = (0.0, 0.0);
+    for q in 0..200 {
+        s.step(&mut g).unwrap();
+        assert!(match conductor {
+            Conductor::Pec => g.ez[200..].iter().all(|v| *v == 0.0),
+            Conductor::Pmc => g.hy[200..].iter().all(|v| *v == 0.0),
+        });
+
+        // The pulse heading left passes node 100 by step 100; its
+        // reflection arrives after step 130.
+        if q < 100 {
+            incident = largest(incident, g.ez[100]);
+        } else {
+            reflected = largest(reflected, g.ez[100]);
+        }
+    }
+
+    (incident, reflected)
+}
+
+/// A PEC reflects the electric field with the opposite sign, a PMC with the
+/// same sign.
+#[test]
+fn test_reflection_1d() {
+    let (incident, reflected) = reflect_1d(Conductor::Pec);
+    assert!(
+        (reflected + incident).abs() < 0.01 * incident,
+        "{} {}",
+        incident,
+        reflected
+    );
+
+    let (incident, reflected) = reflect_1d(Conductor::Pmc);
+    assert!(
+        (reflected - incident).abs() < 0.01 * incident,
+        "{} {}",
+        incident,
+        reflected
+    );
+}
+
+/// A PEC cylinder matches zeroing 'ceze' and 'cezh' by hand.
+#[test]
+fn test_pec_cylinder_2d() {
+    let (x_sz, y_sz) = (60, 50);
+    let circle = Circle::new([35.0, 25.0], 8.0);
+    let source = || PointSource::hard(Field::Ez, [15, 25, 0], Gaussian::new(20.0, 6.0));
+
+    let mut g = Grid::new_2d(x_sz, y_sz, None);
+    let mut c = Conductors::new(&g);
+    c.add(&g, &circle, Conductor::Pec);
+
+    let mut s = sim(GridDimension::Two(Polarization::Magnetic));
+    s.set_conductors(Some(c));
+    s.add_source(source());
+
+    let mut manual = Grid::new_2d(x_sz, y_sz, None);
+    for m in 0..x_sz {
+        for n in 0..y_sz {
+            let (x, y) = (m as f64 - 35.0, n as f64 - 25.0);
+            if x * x + y * y <= 64.0 {
+                manual.ceze[m * y_sz + n] = 0.0;
+                manual.cezh[m * y_sz + n] = 0.0;
+            }
+        }
+    }
+
+    let mut s_manual = sim(GridDimension::Two(Polarization::Magnetic));
+    s_manual.add_source(source());
+
+    for _ in 0..120 {
+        s.step(&mut g).unwrap();
+        s_manual.step(&mut manual).unwrap();
+    }
+
+    assert_eq!(g.ez, manual.ez);
+    assert_eq!(g.hx, manual.hx);
+    assert_eq!(g.hy, manual.hy);
+    assert!(g.ez.iter().any(|v| v.abs() > 0.01));
+}
+
+/// A PMC zeroes 'hz' of a TE^z grid within it, and a PEC sphere zeroes
+/// every electric component of a 3D grid within it.
+#[test]
+fn test_te_and_3d() {
+    let mut g = Grid::new_2d_te(40, 40, None);
+    let mut c = Conductors::new(&g);
+    let circle = Circle::new([25.0, 20.0], 6.0);
+    c.add(&g, &circle, Conductor::Pmc);
+    assert!(!c.is_empty());
+
+    let mut s = sim(GridDimension::Two(Polarization::Electric));
+    s.set_conductors(Some(c));
+    s.add_source(PointSource::soft(
+        Field::Hz,
+        [10, 20, 0],
+        Gaussian::new(15.0, 5.0),
+    ));
+    s.step_mul(&mut g, 80).unwrap();
+
+    assert_eq!(g.hz[25 * 40 + 20], 0.0);
+    assert!(g.hz[15 * 40 + 20].abs() > 0.0);
+
+    let mut g = Grid::new_3d(24, 24, 24, None);
+    let mut c = Conductors::new(&g);
+    c.add(&g, &Sphere::new([14.0; 3], 5.0), Conductor::Pec);
+
+    let mut s = sim(GridDimension::Three);
+    s.set_conductors(Some(c));
+    s.add_source(PointSource::soft(
+        Field::Ez,
+        [6, 6, 6],
+        Gaussian::new(15.0, 5.0),
+    ));
+    s.step_mul(&mut g, 60).unwrap();
+
+    let i = (14 * 24 + 14) * 24 + 14;
+    assert_eq!((g.ex[i], g.ey[i], g.ez[i]), (0.0, 0.0, 0.0));
+    assert!(g.ez.iter().any(|v| v.abs() > 0.0));
+}
+
+/// Conductors built for one grid aren't stepped with a grid of another size
+/// or polarization.
+#[test]
+fn test_other_grid() {
+    let g = Grid::new_2d(20, 20, None);
+    let mut c = Conductors::new(&g);
+    c.add(&g, &Circle::new([10.0, 10.0], 4.0), Conductor::Pec);
+
+    let mut s = sim(GridDimension::Two(Polarization::Magnetic));
+    s.set_conductors(Some(c.clone()));
+    let mut g = Grid::new_2d(30, 20, None);
+    match s.step(&mut g) {
+        Err(e @ FDTDError::Shape { .. }) => {
+            assert!(
+                e.to_string().contains("400 rather than the grid's 600"),
+                "{}",
+                e
+            )
+        }
+        r => panic!("{:?}", r),
+    }
+
+    let mut s = sim(GridDimension::Two(Polarization::Electric));
+    s.set_conductors(Some(c));
+    let mut g = Grid::new_2d_te(20, 20, None);
+    assert!(matches!(
+        s.step(&mut g),
+        Err(FDTDError::Shape {
+            field: Field::Hx,
+            ..
+        })
+    ));
+}
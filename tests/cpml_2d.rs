@@ -0,0 +1,80 @@
+// tests/cpml_2d.rs
+/// Compare a CPML-terminated grid against a grid large enough that no
+/// reflection returns within the run.
+use fdtd::cpml::{Cpml, CpmlParams};
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension, Polarization};
+use fdtd::grid::Grid;
+use fdtd::waveform::{Gaussian, Waveform};
+
+mod util;
+
+const SMALL: usize = 60;
+const LARGE: usize = 260;
+const STEPS: usize = 250;
+
+// Observation point, relative to the source; close to the layer.
+const PROBE: (usize, usize) = (18, 7);
+
+/// Record Ez (TM^z) or Hz (TE^z) at the probe while exciting the center of
+/// the grid.
+fn run(g: &mut Grid, dimension: GridDimension, cpml: Option<Cpml>) -> Vec<f64> {
+    let center = (g.x_sz / 2) * g.y_sz + g.y_sz / 2;
+    let probe = (g.x_sz / 2 - PROBE.0) * g.y_sz + g.y_sz / 2 + PROBE.1;
+    let is_tm = matches!(dimension, GridDimension::Two(Polarization::Magnetic));
+    let pulse = Gaussian::new(30.0, 10.0);
+
+    let post_magnetic = |t: usize, g: &mut Grid| {
+        if !is_tm {
+            g.hz[center] += pulse.value(t as f64);
+        }
+    };
+
+    let post_electric = |t: usize, g: &mut Grid| {
+        if is_tm {
+            g.ez[center] += pulse.value(t as f64);
+        }
+    };
+
+    let mut sim = FDTDSim::new(
+        Some(dimension),
+        Some(Backend::Native),
+        Some(post_magnetic),
+        Some(post_electric),
+        None,
+    )
+    .unwrap();
+    sim.set_cpml(cpml).unwrap();
+
+    let mut res = Vec::with_capacity(STEPS);
+    for _ in 0..STEPS {
+        sim.step(g).unwrap();
+        res.push(if is_tm { g.ez[probe] } else { g.hz[probe] });
+    }
+
+    res
+}
+
+fn check(new: fn(usize, usize, Option<f64>) -> Grid, dimension: GridDimension) {
+    let mut reference = new(LARGE, LARGE, None);
+    let reference = run(&mut reference, dimension, None);
+
+    let mut pec = new(SMALL, SMALL, None);
+    let pec = run(&mut pec, dimension, None);
+
+    let mut g = new(SMALL, SMALL, None);
+    let cpml = Cpml::new(&g, dimension, &CpmlParams::default());
+    let absorbed = run(&mut g, dimension, Some(cpml));
+
+    assert!(util::record::relative_error(&pec, &reference) > 0.1);
+    assert!(util::record::relative_error(&absorbed, &reference) < 1e-3);
+}
+
+#[test]
+fn test_cpml_tm() {
+    check(Grid::new_2d, GridDimension::Two(Polarization::Magnetic));
+}
+
+#[test]
+fn test_cpml_te() {
+    check(Grid::new_2d_te, GridDimension::Two(Polarization::Electric));
+}
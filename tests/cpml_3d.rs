@@ -0,0 +1,93 @@
+// tests/cpml_3d.rs
+/// Compare a CPML-terminated 3D grid against a grid large enough that no
+/// reflection returns within the run.
+use fdtd::cpml::{Cpml, CpmlFaces, CpmlParams};
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension};
+use fdtd::grid::Grid;
+use fdtd::waveform::{Gaussian, Waveform};
+
+mod util;
+
+const SMALL: usize = 32;
+const LARGE: usize = 64;
+const STEPS: usize = 80;
+
+// Observation point, relative to the source; close to the layer.
+const PROBE: (usize, usize, usize) = (8, 3, 2);
+
+/// Record Ez at the probe while exciting the center of the grid.
+fn run(g: &mut Grid, cpml: Option<Cpml>) -> Vec<f64> {
+    let idx = |x: usize, y: usize, z: usize| (x * g.y_sz + y) * g.z_sz + z;
+    let (cx, cy, cz) = (g.x_sz / 2, g.y_sz / 2, g.z_sz / 2);
+    let center = idx(cx, cy, cz);
+    let probe = idx(cx - PROBE.0, cy + PROBE.1, cz + PROBE.2);
+    let pulse = Gaussian::new(20.0, 6.0);
+
+    let post_magnetic = |_t: usize, _g: &mut Grid| {};
+    let post_electric = |t: usize, g: &mut Grid| {
+        g.ez[center] += pulse.value(t as f64);
+    };
+
+    let mut sim = FDTDSim::new(
+        Some(GridDimension::Three),
+        Some(Backend::Native),
+        Some(post_magnetic),
+        Some(post_electric),
+        None,
+    )
+    .unwrap();
+    sim.set_post_magnetic(None);
+    sim.set_cpml(cpml).unwrap();
+
+    let mut res = Vec::with_capacity(STEPS);
+    for _ in 0..STEPS {
+        sim.step(g).unwrap();
+        res.push(g.ez[probe]);
+    }
+
+    res
+}
+
+fn params(faces: CpmlFaces) -> CpmlParams {
+    CpmlParams {
+        thickness: 6,
+        faces,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_cpml_3d() {
+    let mut reference = Grid::new_3d(LARGE, LARGE, LARGE, None);
+    let reference = run(&mut reference, None);
+
+    let mut pec = Grid::new_3d(SMALL, SMALL, SMALL, None);
+    let pec = run(&mut pec, None);
+
+    let mut g = Grid::new_3d(SMALL, SMALL, SMALL, None);
+    let cpml = Cpml::new(&g, GridDimension::Three, &params(CpmlFaces::default()));
+    let absorbed = run(&mut g, Some(cpml));
+
+    assert!(util::record::relative_error(&pec, &reference) > 0.1);
+    assert!(util::record::relative_error(&absorbed, &reference) < 1e-3);
+}
+
+#[test]
+fn test_no_faces() {
+    let faces = CpmlFaces {
+        x_lo: false,
+        x_hi: false,
+        y_lo: false,
+        y_hi: false,
+        z_lo: false,
+        z_hi: false,
+    };
+
+    let mut pec = Grid::new_3d(SMALL, SMALL, SMALL, None);
+    let pec = run(&mut pec, None);
+
+    // With every face disabled the layer must leave the grid untouched.
+    let mut g = Grid::new_3d(SMALL, SMALL, SMALL, None);
+    let cpml = Cpml::new(&g, GridDimension::Three, &params(faces));
+    assert_eq!(run(&mut g, Some(cpml)), pec);
+}
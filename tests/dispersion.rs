@@ -0,0 +1,267 @@
+// tests/dispersion.rs
+/// Check dispersive media against their instantaneous limits.
+use fdtd::dispersion::{Dispersion, DispersiveMaterial, Pole};
+use fdtd::error::FDTDError;
+use fdtd::fdtd::{GridDimension, Polarization};
+use fdtd::geometry::{Block, HalfSpace, Sphere};
+use fdtd::grid::{Field, Grid};
+use fdtd::material::Material;
+use fdtd::sources::PointSource;
+use fdtd::waveform::{DiffGaussian, Gaussian};
+
+mod util;
+use util::sim::sim;
+
+const SIZE: usize = 800;
+const STEPS: usize = 600;
+
+/// Run a slowly varying pulse into a 1D grid half filled with 'm', or with
+/// 'instantaneous' when 'm' is None, and return the final field.
+fn run_1d(m: Option<&DispersiveMaterial>, instantaneous: Material) -> Vec<f64> {
+    let mut g = Grid::new_1d(SIZE);
+    let mut s = sim(GridDimension::One);
+
+    // The source sits in vacuum, to the left of the medium.
+    let medium = HalfSpace::new([300.0, 0.0, 0.0], [1.0, 0.0, 0.0]);
+    match m {
+        Some(m) => {
+            let mut d = Dispersion::new();
+            d.paint(&mut g, &medium, m);
+            s.set_dispersion(Some(d)).unwrap();
+        }
+        None => g.paint(&medium, &instantaneous),
+    }
+
+    s.add_source(PointSource::soft(
+        Field::Ez,
+        [200, 0, 0],
+        Gaussian::new(150.0, 40.0),
+    ));
+    s.step_mul(&mut g, STEPS).unwrap();
+    g.ez
+}
+
+/// Largest difference between two fields relative to the peak of the
+/// second.
+fn difference(a: &[f64], b: &[f64]) -> f64 {
+    let peak = b.iter().fold(0.0f64, |acc, v| acc.max(v.abs()));
+    let diff = a
+        .iter()
+        .zip(b)
+        .fold(0.0f64, |acc, (a, b)| acc.max((a - b).abs()));
+    diff / peak
+}
+
+#[test]
+fn test_susceptibility() {
+    let drude = Pole::Drude {
+        omega_p: 0.5,
+        gamma: 0.1,
+    };
+    let (re, im) = drude.susceptibility(0.2);
+    assert!((re + 0.25 / (0.04 + 0.01)).abs() < 1e-12);
+    assert!((im + 0.25 * 0.1 / 0.2 / (0.04 + 0.01)).abs() < 1e-12);
+
+    // The static permittivity of Debye and Lorentz poles.
+    let m = DispersiveMaterial::new(
+        Material::dielectric(2.0),
+        vec![
+            Pole::Debye {
+                delta_eps: 1.0,
+                tau: 3.0,
+            },
+            Pole::Lorentz {
+                delta_eps: 0.5,
+                omega_0: 0.4,
+                gamma: 0.1,
+            },
+        ],
+    );
+    assert_eq!(m.permittivity(0.0), (3.5, 0.0));
+}
+
+/// Poles without any strength leave the grid unchanged.
+#[test]
+fn test_empty_poles() {
+    let m = DispersiveMaterial::new(
+        Material::dielectric(2.0),
+        vec![
+            Pole::Debye {
+                delta_eps: 0.0,
+                tau: 3.0,
+            },
+            Pole::Drude {
+                omega_p: 0.0,
+                gamma: 0.1,
+            },
+            Pole::Lorentz {
+                delta_eps: 0.0,
+                omega_0: 0.4,
+                gamma: 0.1,
+            },
+        ],
+    );
+
+    let dispersive = run_1d(Some(&m), Material::VACUUM);
+    let instantaneous = run_1d(None, Material::dielectric(2.0));
+    assert!(difference(&dispersive, &instantaneous) < 1e-12);
+}
+
+/// Well below their relaxation frequency or resonance, Debye and Lorentz
+/// media behave as their static permittivity.
+#[test]
+fn test_static_limit() {
+    let instantaneous = run_1d(None, Material::dielectric(4.0));
+
+    let debye = DispersiveMaterial::new(
+        Material::VACUUM,
+        vec![Pole::Debye {
+            delta_eps: 3.0,
+            tau: 0.25,
+        }],
+    );
+    let e = difference(&run_1d(Some(&debye), Material::VACUUM), &instantaneous);
+    assert!(e < 0.05, "{}", e);
+
+    let lorentz = DispersiveMaterial::new(
+        Material::dielectric(2.0),
+        vec![Pole::Lorentz {
+            delta_eps: 2.0,
+            omega_0: 1.0,
+            gamma: 0.1,
+        }],
+    );
+    let e = difference(&run_1d(Some(&lorentz), Material::VACUUM), &instantaneous);
+    assert!(e < 0.02, "{}", e);
+
+    // A slow relaxation responds too late to slow the pulse down.
+    let slow = DispersiveMaterial::new(
+        Material::VACUUM,
+        vec![Pole::Debye {
+            delta_eps: 3.0,
+            tau: 1000.0,
+        }],
+    );
+    let e = difference(&run_1d(Some(&slow), Material::VACUUM), &instantaneous);
+    assert!(e > 0.5, "{}", e);
+}
+
+/// A pulse well below the plasma frequency reflects off a Drude metal.
+#[test]
+fn test_drude_reflection() {
+    let metal = DispersiveMaterial::new(
+        Material::VACUUM,
+        vec![Pole::Drude {
+            omega_p: 0.5,
+            gamma: 0.01,
+        }],
+    );
+
+    let mut g = Grid::new_1d(SIZE);
+    let mut d = Dispersion::new();
+    d.paint(
+        &mut g,
+        &HalfSpace::new([500.0, 0.0, 0.0], [1.0, 0.0, 0.0]),
+        &metal,
+    );
+
+    let mut s = sim(GridDimension::One);
+    s.set_dispersion(Some(d)).unwrap();
+    s.add_source(PointSource::soft(
+        Field::Ez,
+        [300, 0, 0],
+        DiffGaussian::new(60.0, 15.0),
+    ));
+
+    let (mut incident, mut reflected, mut transmitted) = (0.0f64, 0.0f64, 0.0f64);
+    for q in 0..700 {
+        s.step(&mut g).unwrap();
+
+        // The pulse heading left passes node 200 by step 250; the
+        // reflected pulse arrives after step 450.
+        if q < 300 {
+            incident = incident.max(g.ez[200].abs());
+        } else {
+            reflected = reflected.max(g.ez[200].abs());
+        }
+        transmitted = transmitted.max(g.ez[520].abs());
+    }
+
+    assert!(reflected > 0.9 * incident, "{} {}", reflected, incident);
+    assert!(
+        transmitted < 0.01 * incident,
+        "{} {}",
+        transmitted,
+        incident
+    );
+}
+
+/// Dispersive media stay stable in 3D, and nodes taken over by a later
+/// medium without poles stop being dispersive.
+#[test]
+fn test_3d() {
+    let mut g = Grid::new_3d(24, 24, 24, None);
+    let mut d = Dispersion::new();
+
+    let metal = DispersiveMaterial::new(
+        Material::VACUUM,
+        vec![Pole::Drude {
+            omega_p: 0.8,
+            gamma: 0.05,
+        }],
+    );
+    d.paint(&mut g, &Sphere::new([12.0; 3], 6.0), &metal);
+    d.paint(
+        &mut g,
+        &Block::new([0.0; 3], [24.0, 24.0, 12.0]),
+        &DispersiveMaterial::new(Material::dielectric(2.0), vec![]),
+    );
+
+    let mut s = sim(GridDimension::Three);
+    s.set_dispersion(Some(d)).unwrap();
+    s.add_source(PointSource::soft(
+        Field::Ez,
+        [12, 12, 3],
+        DiffGaussian::new(20.0, 5.0),
+    ));
+    s.step_mul(&mut g, 400).unwrap();
+
+    let peak = [&g.ex, &g.ey, &g.ez]
+        .iter()
+        .flat_map(|v| v.iter())
+        .fold(0.0f64, |acc, v| acc.max(v.abs()));
+    assert!(peak.is_finite() && peak < 1.0, "{}", peak);
+}
+
+/// Media painted into one grid aren't stepped with a smaller one.
+#[test]
+fn test_other_grid() {
+    let mut g = Grid::new_2d(30, 30, None);
+    let mut d = Dispersion::new();
+    let lorentz = DispersiveMaterial::new(
+        Material::VACUUM,
+        vec![Pole::Lorentz {
+            delta_eps: 1.0,
+            omega_0: 0.3,
+            gamma: 0.05,
+        }],
+    );
+    d.paint(
+        &mut g,
+        &Block::new([20.0, 20.0, 0.0], [25.0, 25.0, 1.0]),
+        &lorentz,
+    );
+
+    let mut s = sim(GridDimension::Two(Polarization::Magnetic));
+    s.set_dispersion(Some(d)).unwrap();
+    s.step(&mut g).unwrap();
+
+    let mut g = Grid::new_2d(20, 20, None);
+    assert!(matches!(
+        s.step(&mut g),
+        Err(FDTDError::Node {
+            field: Field::Ez,
+            ..
+        })
+    ));
+}
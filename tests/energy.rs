@@ -0,0 +1,110 @@
+// tests/energy.rs
+/// Check energy diagnostics against closed cavities, lossy materials and
+/// absorbing boundaries.
+use fdtd::cpml::{Cpml, CpmlParams};
+use fdtd::energy::{self, EnergyMonitor};
+use fdtd::fdtd::{GridDimension, Polarization};
+use fdtd::grid::{Grid, IMP0};
+use fdtd::material::Material;
+use fdtd::monitor::Region;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+mod util;
+use util::sim::sim;
+
+/// The energy of a node is weighted by the material it holds.
+#[test]
+fn test_stored() {
+    let mut g = Grid::new_1d(10);
+    g.set_material([3, 0, 0], &Material::dielectric(4.0));
+    g.set_material([5, 0, 0], &Material::new(1.0, 2.0, 0.0, 0.0));
+    g.ez[3] = 1.0;
+    g.ez[7] = 2.0;
+    g.hy[5] = 1.0 / IMP0;
+
+    let e = energy::stored(&g, Region::all(&g));
+    assert!((e.electric - 4.0).abs() < 1e-12, "{}", e.electric);
+    assert!((e.magnetic - 1.0).abs() < 1e-12, "{}", e.magnetic);
+    assert!((e.total() - 5.0).abs() < 1e-12);
+
+    let e = energy::stored(&g, Region::line([0, 0, 0], 0, 5));
+    assert!((e.electric - 2.0).abs() < 1e-12 && e.magnetic == 0.0);
+}
+
+/// A pulse bouncing within a closed 1D cavity keeps its energy, and a lossy
+/// layer within the cavity absorbs it.
+#[test]
+fn test_cavity_1d() {
+    for lossy in [false, true] {
+        let mut g = Grid::new_1d(200);
+        if lossy {
+            g.set_materials(|[x, _, _]| {
+                if x >= 150 {
+                    Material::matched(2.0, 1.0, 0.0005)
+                } else {
+                    Material::VACUUM
+                }
+            });
+        }
+        for (x, v) in g.ez.iter_mut().enumerate() {
+            *v = (-((x as f64 - 80.0) / 8.0).powi(2)).exp();
+        }
+
+        let monitor = Rc::new(RefCell::new(EnergyMonitor::new(&g, Region::all(&g))));
+        let mut s = sim(GridDimension::One);
+        s.add_observer(monitor.clone());
+        s.step_mul(&mut g, 1000).unwrap();
+
+        let m = monitor.borrow();
+        assert_eq!(m.start(), 1.5);
+        assert_eq!(m.stored().len(), 999);
+        assert!(m.outflow().iter().all(|o| *o == 0.0));
+
+        let balance = m.balance();
+        for b in &balance {
+            assert!((b / balance[0] - 1.0).abs() < 1e-9, "{} {}", b, balance[0]);
+        }
+
+        let last = m.stored().last().unwrap().total();
+        if lossy {
+            assert!(last < 0.1 * balance[0], "{} {}", last, balance[0]);
+        } else {
+            assert!((last / balance[0] - 1.0).abs() < 1e-9);
+        }
+    }
+}
+
+/// The energy of a pulse flows out of the region within a CPML, which
+/// absorbs it.
+#[test]
+fn test_cpml_2d() {
+    let size = 80;
+    let dimension = GridDimension::Two(Polarization::Magnetic);
+    let mut g = Grid::new_2d(size, size, None);
+    let c = size as f64 / 2.0;
+    for x in 0..size {
+        for y in 0..size {
+            let r2 = (x as f64 - c).powi(2) + (y as f64 - c).powi(2);
+            g.ez[x * size + y] = (-r2 / 25.0).exp();
+        }
+    }
+
+    let interior = Region::new([12, 12, 0], [size - 12, size - 12, 1]);
+    let monitor = Rc::new(RefCell::new(EnergyMonitor::new(&g, interior)));
+    let mut s = sim(dimension);
+    s.set_cpml(Some(Cpml::new(&g, dimension, &CpmlParams::default())))
+        .unwrap();
+    s.add_observer(monitor.clone());
+    s.step_mul(&mut g, 400).unwrap();
+
+    let m = monitor.borrow();
+    let initial = m.stored()[0].total();
+    let last = m.stored().last().unwrap().total();
+    assert!(last < 1e-3 * initial, "{} {}", last, initial);
+    assert!(m.absorbed().iter().all(|a| *a == 0.0));
+
+    for b in m.balance() {
+        assert!((b / initial - 1.0).abs() < 1e-9, "{} {}", b, initial);
+    }
+}
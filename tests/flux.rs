@@ -0,0 +1,173 @@
+// tests/flux.rs
+/// Check Poynting flux monitors against a dielectric interface and the
+/// conservation of energy.
+use fdtd::cpml::{Cpml, CpmlParams};
+use fdtd::error::FDTDError;
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension, Polarization};
+use fdtd::flux::FluxMonitor;
+use fdtd::grid::{Field, Grid};
+use fdtd::material::Material;
+use fdtd::monitor::Region;
+use fdtd::sources::PointSource;
+use fdtd::tfsf::Tfsf1d;
+use fdtd::waveform::{DiffGaussian, Gaussian};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type Sim = FDTDSim<fn(usize, &mut Grid), fn(usize, &mut Grid)>;
+
+const OMEGAS: [f64; 2] = [0.05, 0.1];
+
+/// Send a pulse from a TFSF boundary towards a dielectric half space from
+/// node 300 on, if 'epsr' is given, and return the flux monitors in front
+/// of and beyond the interface.
+fn run_1d(epsr: Option<f64>) -> (FluxMonitor, FluxMonitor) {
+    let mut g = Grid::new_1d(600);
+    if let Some(epsr) = epsr {
+        g.set_materials(|[x, _, _]| {
+            if x < 300 {
+                Material::VACUUM
+            } else {
+                Material::dielectric(epsr)
+            }
+        });
+    }
+
+    let front = Rc::new(RefCell::new(
+        FluxMonitor::plane(&g, 0, 100, &OMEGAS).unwrap(),
+    ));
+    let back = Rc::new(RefCell::new(
+        FluxMonitor::plane(&g, 0, 350, &OMEGAS).unwrap(),
+    ));
+
+    let tfsf = Tfsf1d::new(50, Gaussian::new(30.0, 10.0), Material::VACUUM).unwrap();
+    let mut s = FDTDSim::new(
+        Some(GridDimension::One),
+        Some(Backend::Native),
+        Some(|t: usize, g: &mut Grid| tfsf.magnetic(t, g)),
+        Some(|t: usize, g: &mut Grid| tfsf.electric(t, g)),
+        None,
+    )
+    .unwrap();
+    s.add_observer(front.clone());
+    s.add_observer(back.clone());
+    s.step_mul(&mut g, 600).unwrap();
+
+    drop(s);
+    (
+        Rc::try_unwrap(front).unwrap().into_inner(),
+        Rc::try_unwrap(back).unwrap().into_inner(),
+    )
+}
+
+/// A dielectric interface with 'εr = 9' reflects a quarter of the power
+/// that falls on it and transmits the rest, and the energy through either
+/// monitor is the same.
+#[test]
+fn test_interface_1d() {
+    let (incident, through) = run_1d(None);
+    let (mut front, back) = run_1d(Some(9.0));
+
+    let norm = incident.spectrum();
+    for (a, b) in norm.iter().zip(through.spectrum()) {
+        assert!((a / b - 1.0).abs() < 1e-6, "{} {}", a, b);
+        assert!(*a > 0.0);
+    }
+
+    let transmitted = back.spectrum();
+    front.subtract(&incident);
+    let reflected = front.spectrum();
+    for i in 0..OMEGAS.len() {
+        let (r, t) = (-reflected[i] / norm[i], transmitted[i] / norm[i]);
+        assert!((r - 0.25).abs() < 0.01, "{}", r);
+        assert!((t - 0.75).abs() < 0.01, "{}", t);
+    }
+
+    assert_eq!(front.start(), 1.5);
+    assert_eq!(front.series().len(), 599);
+    let (a, b): (f64, f64) = (front.series().iter().sum(), back.series().iter().sum());
+    assert!((a / b - 1.0).abs() < 1e-3, "{} {}", a, b);
+    assert!(front.series().iter().any(|v| *v < 0.0));
+}
+
+/// The power flowing out of a box around a source is the same as the power
+/// flowing out of a larger box, for either polarization.
+#[test]
+fn test_conservation_2d() {
+    let size = 120;
+    for (polarization, f) in [
+        (Polarization::Magnetic, Field::Ez),
+        (Polarization::Electric, Field::Hz),
+    ] {
+        let dimension = GridDimension::Two(polarization);
+        let mut g = match polarization {
+            Polarization::Magnetic => Grid::new_2d(size, size, None),
+            Polarization::Electric => Grid::new_2d_te(size, size, None),
+        };
+
+        // The sides of a box 'half' cells either side of the centre, with
+        // their outward direction.
+        let c = size / 2;
+        let sides = |g: &Grid, half: usize| -> Vec<(Rc<RefCell<FluxMonitor>>, f64)> {
+            let (lo, hi) = (c - half, c + half);
+            [(0, lo, -1.0), (0, hi, 1.0), (1, lo, -1.0), (1, hi, 1.0)]
+                .into_iter()
+                .map(|(axis, index, sign)| {
+                    let mut min = [lo, lo, 0];
+                    min[axis] = index;
+                    let region = Region::line(min, 1 - axis, 2 * half);
+                    let m = FluxMonitor::new(g, region, axis, &OMEGAS).unwrap();
+                    (Rc::new(RefCell::new(m)), sign)
+                })
+                .collect()
+        };
+        let (inner, outer) = (sides(&g, 10), sides(&g, 30));
+
+        let mut s: Sim =
+            FDTDSim::new(Some(dimension), Some(Backend::Native), None, None, None).unwrap();
+        s.set_cpml(Some(Cpml::new(&g, dimension, &CpmlParams::default())))
+            .unwrap();
+        s.add_source(PointSource::current(
+            f,
+            [c, c, 0],
+            DiffGaussian::new(40.0, 10.0),
+        ));
+        for (m, _) in inner.iter().chain(&outer) {
+            s.add_observer(m.clone());
+        }
+        s.step_mul(&mut g, 500).unwrap();
+
+        let total = |sides: &[(Rc<RefCell<FluxMonitor>>, f64)]| -> Vec<f64> {
+            (0..OMEGAS.len())
+                .map(|i| {
+                    sides
+                        .iter()
+                        .map(|(m, sign)| sign * m.borrow().spectrum()[i])
+                        .sum()
+                })
+                .collect()
+        };
+        for (a, b) in total(&inner).iter().zip(total(&outer)) {
+            assert!(*a > 0.0);
+            assert!((a / b - 1.0).abs() < 1e-3, "{:?} {} {}", polarization, a, b);
+        }
+    }
+}
+
+/// A flux region must be one node thick along its axis, past the first
+/// node, on an axis the grid has.
+#[test]
+fn test_region() {
+    let g = Grid::new_2d(40, 40, None);
+    for (region, axis) in [
+        (Region::new([0, 0, 0], [1, 40, 0]), 0),
+        (Region::new([10, 0, 0], [12, 40, 0]), 0),
+        (Region::new([0, 0, 5], [40, 40, 6]), 2),
+    ] {
+        assert!(matches!(
+            FluxMonitor::new(&g, region, axis, &OMEGAS),
+            Err(FDTDError::Region { .. })
+        ));
+    }
+    assert!(FluxMonitor::plane(&g, 1, 10, &OMEGAS).is_ok());
+}
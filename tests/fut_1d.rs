@@ -166,3 +166,41 @@ fn test_random_grid() {
         assert_eq!(util::grid_eq::grid_eq(&grid1, &grid4), true);
     }
 }
+
+/// A post-electric function hard-sourcing 'Ez' at the centre of the grid.
+fn hard_source(t: usize, g: &mut Grid) {
+    g.ez[SIZE / 2] = (0.3 * t as f64).sin();
+}
+
+/// The post-electric function is called on each step taken by
+/// step_single_futhark, as it is natively.
+#[test]
+fn test_post_electric() {
+    let mut grid1 = util::create_grid::default_grid(SIZE, None, None, GridDimension::One);
+    let mut grid2 = grid1.clone();
+
+    let no_magnetic: Option<fn(usize, &mut Grid)> = None;
+    let mut sim_single = FDTDSim::new(
+        Some(GridDimension::One),
+        Some(Backend::Futhark),
+        no_magnetic,
+        Some(hard_source),
+        None,
+    )
+    .unwrap();
+    let mut sim_native = FDTDSim::new(
+        Some(GridDimension::One),
+        Some(Backend::Native),
+        no_magnetic,
+        Some(hard_source),
+        None,
+    )
+    .unwrap();
+
+    for _ in 0..50 {
+        assert!(sim_single.step(&mut grid1).is_ok());
+        assert!(sim_native.step(&mut grid2).is_ok());
+        assert!(util::grid_eq::grid_eq(&grid1, &grid2));
+    }
+    assert!(grid1.ez.iter().any(|v| *v != 0.0));
+}
@@ -179,3 +179,29 @@ fn test_random_grid() {
         assert_eq!(util::grid_eq::grid_eq(&grid1, &grid4), true);
     }
 }
+
+/// A grid of more than one node along each axis, so each update has
+/// differences to take along every axis.
+#[test]
+fn test_random_grid_cube() {
+    let mut grid1 = util::create_grid::random_grid(4, Some(5), Some(6), GridDimension::Three);
+    let mut grid2 = grid1.clone();
+    let mut grid3 = grid1.clone();
+    let mut grid4 = grid1.clone();
+
+    let mut sim_single = setup_step_single_futhark().unwrap();
+    let mut sim_split = setup_step_split_futhark().unwrap();
+    let mut sim_mul = setup_step_mul_futhark().unwrap();
+    let mut sim_native = setup_step_native().unwrap();
+
+    for _ in 0..50 {
+        assert!(sim_single.step(&mut grid1).is_ok());
+        assert!(sim_split.step(&mut grid2).is_ok());
+        assert!(sim_mul.step(&mut grid3).is_ok());
+        assert!(sim_native.step(&mut grid4).is_ok());
+
+        assert!(util::grid_eq::grid_eq(&grid1, &grid4));
+        assert!(util::grid_eq::grid_eq(&grid2, &grid4));
+        assert!(util::grid_eq::grid_eq(&grid3, &grid4));
+    }
+}
@@ -0,0 +1,167 @@
+// tests/fut_conductor.rs
+/// Prove some level of equivalency of the Futhark and native stepping of
+/// perfect conductors.
+use fdtd::conductor::{Conductor, Conductors};
+use fdtd::error;
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension, Polarization};
+use fdtd::geometry::{Circle, HalfSpace, Sphere};
+use fdtd::grid::{Field, Grid};
+use rand::thread_rng;
+use rand::Rng;
+
+mod util;
+
+const SIZE_X: usize = 12;
+const SIZE_Y: usize = 10;
+const SIZE_Z: usize = 8;
+
+/// Return a simulation that *should* call step_mul_masked_futhark (we have
+/// neither post_magnetic or post_electric).
+fn setup_step_mul_masked_futhark(
+    dimension: GridDimension,
+    c: &Conductors,
+) -> Result<FDTDSim<impl FnMut(usize, &mut Grid), impl FnMut(usize, &mut Grid)>, error::FDTDError> {
+    let post_magnetic = |_t: usize, _g: &mut Grid| {};
+    let post_electric = |_t: usize, _g: &mut Grid| {};
+
+    let mut res = FDTDSim::new(
+        Some(dimension),
+        Some(Backend::Futhark),
+        Some(post_magnetic),
+        Some(post_electric),
+        None,
+    )?;
+
+    res.set_post_magnetic(None);
+    res.set_post_electric(None);
+    res.set_conductors(Some(c.clone()));
+
+    Ok(res)
+}
+
+/// Return a simulation that *should* call step_split_futhark (we have an
+/// post_electric fn, which conductors can't be stepped with in one go).
+fn setup_step_split_futhark(
+    dimension: GridDimension,
+    c: &Conductors,
+) -> Result<FDTDSim<impl FnMut(usize, &mut Grid), impl FnMut(usize, &mut Grid)>, error::FDTDError> {
+    let post_magnetic = |_t: usize, _g: &mut Grid| {};
+    let post_electric = |_t: usize, _g: &mut Grid| {};
+
+    let mut res = FDTDSim::new(
+        Some(dimension),
+        Some(Backend::Futhark),
+        Some(post_magnetic),
+        Some(post_electric),
+        None,
+    )?;
+
+    res.set_post_magnetic(None);
+    res.set_conductors(Some(c.clone()));
+
+    Ok(res)
+}
+
+/// Return a simulation that *should* call the native backend.
+fn setup_step_native(
+    dimension: GridDimension,
+    c: &Conductors,
+) -> Result<FDTDSim<impl FnMut(usize, &mut Grid), impl FnMut(usize, &mut Grid)>, error::FDTDError> {
+    let post_magnetic = |_t: usize, _g: &mut Grid| {};
+    let post_electric = |_t: usize, _g: &mut Grid| {};
+
+    let mut res = FDTDSim::new(
+        Some(dimension),
+        Some(Backend::Native),
+        Some(post_magnetic),
+        Some(post_electric),
+        None,
+    )?;
+
+    res.set_post_magnetic(None);
+    res.set_post_electric(None);
+    res.set_conductors(Some(c.clone()));
+
+    Ok(res)
+}
+
+/// Create a grid of the given dimension holding random fields, with a PEC
+/// and a PMC region within it.
+fn setup_grid(dimension: GridDimension) -> (Grid, Conductors) {
+    let mut g = util::create_grid::default_grid(SIZE_X, Some(SIZE_Y), Some(SIZE_Z), dimension);
+
+    let mut rng = thread_rng();
+    for f in Field::ALL {
+        if let Some(mut v) = g.view_mut(f) {
+            for x in v.as_mut_slice() {
+                *x = rng.gen_range(-1.0..1.0);
+            }
+        }
+    }
+
+    let mut c = Conductors::new(&g);
+    match dimension {
+        GridDimension::One => {
+            c.add(
+                &g,
+                &HalfSpace::new([8.0, 0.0, 0.0], [1.0, 0.0, 0.0]),
+                Conductor::Pec,
+            );
+            c.add(
+                &g,
+                &HalfSpace::new([2.0, 0.0, 0.0], [-1.0, 0.0, 0.0]),
+                Conductor::Pmc,
+            );
+        }
+        GridDimension::Two(_) => {
+            c.add(&g, &Circle::new([4.0, 4.0], 2.5), Conductor::Pec);
+            c.add(&g, &Circle::new([8.0, 6.0], 2.0), Conductor::Pmc);
+        }
+        GridDimension::Three => {
+            c.add(&g, &Sphere::new([4.0, 4.0, 4.0], 2.5), Conductor::Pec);
+            c.add(&g, &Sphere::new([8.0, 6.0, 4.0], 2.0), Conductor::Pmc);
+        }
+    }
+    assert!(!c.is_empty());
+
+    (g, c)
+}
+
+fn check(dimension: GridDimension) {
+    let (mut grid1, c) = setup_grid(dimension);
+    let mut grid2 = grid1.clone();
+    let mut grid3 = grid1.clone();
+
+    let mut sim_mul = setup_step_mul_masked_futhark(dimension, &c).unwrap();
+    let mut sim_split = setup_step_split_futhark(dimension, &c).unwrap();
+    let mut sim_native = setup_step_native(dimension, &c).unwrap();
+
+    for _ in 0..100 {
+        assert_eq!(sim_mul.step(&mut grid1).is_ok(), true);
+        assert_eq!(sim_split.step(&mut grid2).is_ok(), true);
+        assert_eq!(sim_native.step(&mut grid3).is_ok(), true);
+
+        assert_eq!(util::grid_eq::grid_eq(&grid1, &grid3), true);
+        assert_eq!(util::grid_eq::grid_eq(&grid2, &grid3), true);
+    }
+}
+
+#[test]
+fn test_1d() {
+    check(GridDimension::One);
+}
+
+#[test]
+fn test_2d() {
+    check(GridDimension::Two(Polarization::Magnetic));
+}
+
+#[test]
+fn test_2d_te() {
+    check(GridDimension::Two(Polarization::Electric));
+}
+
+#[test]
+fn test_3d() {
+    check(GridDimension::Three);
+}
@@ -0,0 +1,168 @@
+// tests/fut_periodic.rs
+/// Prove some level of equivalency of the Futhark and native stepping of
+/// periodic boundaries.
+use fdtd::conductor::{Conductor, Conductors};
+use fdtd::cpml::{Cpml, CpmlFaces, CpmlParams};
+use fdtd::error;
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension, Polarization};
+use fdtd::geometry::Circle;
+use fdtd::grid::{Field, Grid};
+use rand::thread_rng;
+use rand::Rng;
+
+mod util;
+
+const SIZE_X: usize = 12;
+const SIZE_Y: usize = 10;
+const SIZE_Z: usize = 16;
+
+/// Return a simulation with the given backend and periodic axes, without
+/// post-{magnetic, electric} functions; on Futhark it *should* call
+/// step_mul_periodic_futhark unless there's anything else to do natively.
+fn setup_sim(
+    dimension: GridDimension,
+    backend: Backend,
+    periodic: [bool; 3],
+) -> Result<FDTDSim<impl FnMut(usize, &mut Grid), impl FnMut(usize, &mut Grid)>, error::FDTDError> {
+    let post_magnetic = |_t: usize, _g: &mut Grid| {};
+    let post_electric = |_t: usize, _g: &mut Grid| {};
+
+    let mut res = FDTDSim::new(
+        Some(dimension),
+        Some(backend),
+        Some(post_magnetic),
+        Some(post_electric),
+        None,
+    )?;
+
+    res.set_post_magnetic(None);
+    res.set_post_electric(None);
+    res.set_periodic(periodic);
+
+    Ok(res)
+}
+
+/// Create a grid of the given dimension holding random fields.
+fn setup_grid(dimension: GridDimension) -> Grid {
+    let mut g = util::create_grid::default_grid(SIZE_X, Some(SIZE_Y), Some(SIZE_Z), dimension);
+
+    let mut rng = thread_rng();
+    for f in Field::ALL {
+        if let Some(mut v) = g.view_mut(f) {
+            for x in v.as_mut_slice() {
+                *x = rng.gen_range(-1.0..1.0);
+            }
+        }
+    }
+
+    g
+}
+
+/// Step 'grid1' with 'sim_futhark' and 'grid2' with 'sim_native', checking
+/// the grids stay equal.
+fn compare<A, B, C, D>(
+    sim_futhark: &mut FDTDSim<A, B>,
+    sim_native: &mut FDTDSim<C, D>,
+    grid1: &mut Grid,
+    grid2: &mut Grid,
+) where
+    A: FnMut(usize, &mut Grid),
+    B: FnMut(usize, &mut Grid),
+    C: FnMut(usize, &mut Grid),
+    D: FnMut(usize, &mut Grid),
+{
+    for _ in 0..50 {
+        assert_eq!(sim_futhark.step(grid1).is_ok(), true);
+        assert_eq!(sim_native.step(grid2).is_ok(), true);
+
+        assert_eq!(util::grid_eq::grid_eq(grid1, grid2), true);
+    }
+}
+
+/// Compare the backends for every combination of the axes the dimension
+/// has being periodic.
+fn check(dimension: GridDimension, axes: usize) {
+    for k in 0..1 << axes {
+        let periodic = [0, 1, 2].map(|a| k & (1 << a) != 0);
+
+        let mut grid1 = setup_grid(dimension);
+        let mut grid2 = grid1.clone();
+
+        let mut sim_futhark = setup_sim(dimension, Backend::Futhark, periodic).unwrap();
+        let mut sim_native = setup_sim(dimension, Backend::Native, periodic).unwrap();
+
+        compare(&mut sim_futhark, &mut sim_native, &mut grid1, &mut grid2);
+    }
+}
+
+#[test]
+fn test_1d() {
+    check(GridDimension::One, 1);
+}
+
+#[test]
+fn test_2d() {
+    check(GridDimension::Two(Polarization::Magnetic), 2);
+}
+
+#[test]
+fn test_2d_te() {
+    check(GridDimension::Two(Polarization::Electric), 2);
+}
+
+#[test]
+fn test_3d() {
+    check(GridDimension::Three, 3);
+}
+
+/// Periodic boundaries combined with a CPML are stepped natively.
+#[test]
+fn test_cpml() {
+    let dimension = GridDimension::Three;
+    let periodic = [true, true, false];
+
+    let mut grid1 = setup_grid(dimension);
+    let mut grid2 = grid1.clone();
+
+    // Line the non-periodic faces only.
+    let params = CpmlParams {
+        thickness: 4,
+        faces: CpmlFaces {
+            x_lo: false,
+            x_hi: false,
+            y_lo: false,
+            y_hi: false,
+            z_lo: true,
+            z_hi: true,
+        },
+        ..Default::default()
+    };
+    let cpml = Cpml::new(&grid1, dimension, &params);
+
+    let mut sim_futhark = setup_sim(dimension, Backend::Futhark, periodic).unwrap();
+    let mut sim_native = setup_sim(dimension, Backend::Native, periodic).unwrap();
+    sim_futhark.set_cpml(Some(cpml.clone())).unwrap();
+    sim_native.set_cpml(Some(cpml)).unwrap();
+
+    compare(&mut sim_futhark, &mut sim_native, &mut grid1, &mut grid2);
+}
+
+/// Periodic boundaries combined with conductors are stepped natively.
+#[test]
+fn test_conductors() {
+    let dimension = GridDimension::Two(Polarization::Magnetic);
+    let periodic = [true, false, false];
+
+    let mut grid1 = setup_grid(dimension);
+    let mut grid2 = grid1.clone();
+
+    let mut c = Conductors::new(&grid1);
+    c.add(&grid1, &Circle::new([6.0, 5.0], 2.5), Conductor::Pec);
+
+    let mut sim_futhark = setup_sim(dimension, Backend::Futhark, periodic).unwrap();
+    let mut sim_native = setup_sim(dimension, Backend::Native, periodic).unwrap();
+    sim_futhark.set_conductors(Some(c.clone()));
+    sim_native.set_conductors(Some(c));
+
+    compare(&mut sim_futhark, &mut sim_native, &mut grid1, &mut grid2);
+}
@@ -0,0 +1,147 @@
+// tests/geometry.rs
+/// Check the geometry primitives and their rasterization onto grids.
+use fdtd::geometry::{Block, Circle, Cylinder, HalfSpace, Polygon, Rectangle, Shape, Sphere};
+use fdtd::grid::{Axis, Grid, IMP0};
+use fdtd::material::Material;
+
+#[test]
+fn test_shapes() {
+    let r = Rectangle::new([1.0, 2.0], [3.0, 4.0]);
+    assert!(r.contains([1.0, 4.0, -10.0]));
+    assert!(!r.contains([0.9, 3.0, 0.0]));
+
+    let b = Block::new([0.0; 3], [1.0, 2.0, 3.0]);
+    assert!(b.contains([1.0, 2.0, 3.0]));
+    assert!(!b.contains([0.5, 0.5, 3.5]));
+
+    let c = Circle::new([5.0, 5.0], 2.0);
+    assert!(c.contains([7.0, 5.0, 3.0]));
+    assert!(!c.contains([6.5, 6.5, 0.0]));
+
+    let s = Sphere::new([5.0; 3], 2.0);
+    assert!(s.contains([6.0, 6.0, 6.0]));
+    assert!(!s.contains([6.5, 6.5, 5.0]));
+
+    let cyl = Cylinder::new([5.0; 3], Axis::X, 2.0, 4.0);
+    assert!(cyl.contains([7.0, 6.0, 6.0]));
+    assert!(!cyl.contains([7.5, 5.0, 5.0]));
+    assert!(!cyl.contains([5.0, 7.5, 5.0]));
+
+    let h = HalfSpace::new([2.0, 0.0, 0.0], [1.0, 1.0, 0.0]);
+    assert!(h.contains([2.0, 0.0, 0.0]));
+    assert!(h.contains([1.5, 1.0, 0.0]));
+    assert!(!h.contains([1.0, 0.5, 9.0]));
+}
+
+#[test]
+fn test_polygon() {
+    // An L shape; the notch at the top-right must be outside.
+    let l = Polygon::new(vec![
+        [0.0, 0.0],
+        [4.0, 0.0],
+        [4.0, 2.0],
+        [2.0, 2.0],
+        [2.0, 4.0],
+        [0.0, 4.0],
+    ]);
+
+    assert!(l.contains([1.0, 1.0, 0.0]));
+    assert!(l.contains([3.0, 1.0, 0.0]));
+    assert!(l.contains([1.0, 3.0, 0.0]));
+    assert!(!l.contains([3.0, 3.0, 0.0]));
+    assert!(!l.contains([5.0, 1.0, 0.0]));
+    assert!(!l.contains([-1.0, 1.0, 0.0]));
+}
+
+#[test]
+fn test_boolean() {
+    let ring = Circle::new([0.0, 0.0], 4.0).difference(Circle::new([0.0, 0.0], 2.0));
+    assert!(ring.contains([3.0, 0.0, 0.0]));
+    assert!(!ring.contains([1.0, 0.0, 0.0]));
+    assert!(!ring.contains([5.0, 0.0, 0.0]));
+
+    let both = ring.union(Rectangle::new([-0.5, -0.5], [0.5, 0.5]));
+    assert!(both.contains([3.0, 0.0, 0.0]));
+    assert!(both.contains([0.0, 0.0, 0.0]));
+    assert!(!both.contains([1.0, 0.0, 0.0]));
+
+    // Shapes chosen at run time.
+    let shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(Sphere::new([0.0; 3], 1.0)),
+        Box::new(Block::new([2.0; 3], [3.0; 3])),
+    ];
+    let any = |p| shapes.iter().any(|s| s.contains(p));
+    assert!(any([2.5; 3]));
+    assert!(!any([1.5; 3]));
+}
+
+/// Painting a circle into a TM^z grid must match the hand-written loop
+/// over the 'ez' nodes, while each 'H' component is sampled half a cell
+/// away.
+#[test]
+fn test_paint_2d() {
+    let (x_sz, y_sz) = (30, 20);
+    let m = Material::dielectric(4.0);
+    let circle = Circle::new([15.0, 10.0], 6.0);
+
+    let mut g = Grid::new_2d(x_sz, y_sz, None);
+    g.paint(&circle, &m);
+
+    for x in 0..x_sz {
+        for y in 0..y_sz {
+            let (dx, dy) = (x as f64 - 15.0, y as f64 - 10.0);
+            let inside = dx * dx + dy * dy <= 36.0;
+            let expected = if inside { 4.0 } else { 1.0 };
+            assert_eq!(g.cezh[x * y_sz + y], g.cdtds * IMP0 / expected);
+        }
+    }
+
+    // The material isn't magnetic, so 'H' coefficients are untouched.
+    let h = Grid::new_2d(x_sz, y_sz, None);
+    assert_eq!(g.chxe, h.chxe);
+    assert_eq!(g.chye, h.chye);
+
+    // 'hy' sits at (x + 1/2, y): node (21, 10) lies outside the circle,
+    // but its 'hy' at 21.5 doesn't.
+    let mut g = Grid::new_2d(x_sz, y_sz, None);
+    g.paint(&circle, &Material::new(1.0, 2.0, 0.0, 0.0));
+    assert_eq!(g.chye[20 * y_sz + 10], g.cdtds / IMP0 / 2.0);
+    assert_eq!(g.chye[21 * y_sz + 10], g.cdtds / IMP0);
+    assert_eq!(g.chxe[21 * y_sz + 10], g.cdtds / IMP0);
+}
+
+/// 'hz' of a TE^z grid sits at the centre of the cell.
+#[test]
+fn test_paint_2d_te() {
+    let mut g = Grid::new_2d_te(10, 10, None);
+    let m = Material::new(1.0, 2.0, 0.0, 0.0);
+    g.paint(&Rectangle::new([2.5, 2.5], [3.5, 3.5]), &m);
+
+    let changed: Vec<usize> = (0..100).filter(|i| g.chze[*i] != g.cdtds / IMP0).collect();
+    assert_eq!(
+        changed,
+        vec![2 * 10 + 2, 2 * 10 + 3, 3 * 10 + 2, 3 * 10 + 3]
+    );
+}
+
+/// The electric components of a 3D cell sit on its edges.
+#[test]
+fn test_paint_3d() {
+    let mut g = Grid::new_3d(8, 8, 8, None);
+    let m = Material::dielectric(2.0);
+
+    // Only 'ez' at node (3, 3, 3), at (3, 3, 3.5), lies in the block; the
+    // half-space lies beyond the grid.
+    g.paint(
+        &Block::new([2.9, 2.9, 3.2], [3.1, 3.1, 3.8])
+            .union(HalfSpace::new([0.0, 0.0, 10.0], [0.0, 0.0, 1.0])),
+        &m,
+    );
+
+    let i = (3 * 8 + 3) * 8 + 3;
+    let vacuum = g.cdtds * IMP0;
+    assert_eq!(g.cezh[i], vacuum / 2.0);
+    assert_eq!(g.cexh[i], vacuum);
+    assert_eq!(g.ceyh[i], vacuum);
+    assert_eq!(g.cezh.iter().filter(|c| **c != vacuum).count(), 1);
+}
@@ -0,0 +1,117 @@
+// tests/grid_type.rs
+/// Check grid types fixed to one dimension step as the grids they wrap, and
+/// can't be stepped in another dimension.
+use fdtd::error::FDTDError;
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension, Polarization};
+use fdtd::geometry::Circle;
+use fdtd::grid::{Field, Grid, Grid1d, Grid2dTe, Grid2dTm, GridType};
+use fdtd::material::Material;
+use fdtd::sources::PointSource;
+use fdtd::waveform::Gaussian;
+
+type Sim<G> = FDTDSim<fn(usize, &mut Grid), fn(usize, &mut Grid), G>;
+
+/// Step 'g' with a soft Gaussian source of 'f' at 'pos'.
+fn run<G: GridType>(dimension: Option<GridDimension>, g: &mut G, f: Field, pos: [usize; 3]) {
+    let mut s: Sim<G> = FDTDSim::new(dimension, Some(Backend::Native), None, None, None).unwrap();
+    s.add_source(PointSource::soft(f, pos, Gaussian::new(30.0, 10.0)));
+    s.step_mul(g, 100).unwrap();
+}
+
+#[test]
+fn test_step() {
+    let mut g = Grid::new_1d(200);
+    let mut typed = Grid1d::new(200);
+    run(Some(GridDimension::One), &mut g, Field::Ez, [100, 0, 0]);
+    run(None, &mut typed, Field::Ez, [100, 0, 0]);
+    assert!(g.ez.iter().any(|v| *v != 0.0));
+    assert_eq!(&g, typed.grid());
+
+    let dimension = GridDimension::Two(Polarization::Electric);
+    let mut g = Grid::new_2d_te(40, 30, None);
+    let mut typed = Grid2dTe::new(40, 30, None);
+    run(Some(dimension), &mut g, Field::Ex, [20, 15, 0]);
+    run(Some(dimension), &mut typed, Field::Ex, [20, 15, 0]);
+    assert_eq!(
+        typed.get(Field::Ey, [21, 16, 0]),
+        g.get(Field::Ey, [21, 16, 0])
+    );
+    assert_eq!(typed.into_inner(), g);
+}
+
+#[test]
+fn test_dimension() {
+    let dimension = GridDimension::Two(Polarization::Magnetic);
+    let s: Result<Sim<Grid2dTm>, _> =
+        FDTDSim::new(Some(GridDimension::Three), None, None, None, None);
+    match s.map(|_| ()) {
+        Err(FDTDError::GridType {
+            dimension: d,
+            expected,
+        }) => {
+            assert_eq!(d, GridDimension::Three);
+            assert_eq!(expected, dimension);
+        }
+        r => panic!("{:?}", r),
+    }
+
+    assert!(Grid2dTm::try_from(Grid::new_2d(10, 10, None)).is_ok());
+    assert!(matches!(
+        Grid2dTm::try_from(Grid::new_2d_te(10, 10, None)),
+        Err(FDTDError::Dimension { .. })
+    ));
+    assert!(matches!(
+        Grid1d::try_from(Grid::new_2d(10, 10, None)),
+        Err(FDTDError::Dimension { .. })
+    ));
+}
+
+/// Grid types are changed through the methods they forward, as the grids
+/// they wrap are.
+#[test]
+fn test_mutation() {
+    let dielectric = Material::dielectric(4.0);
+    let mut g = Grid::new_2d(40, 30, None);
+    let mut typed = Grid2dTm::new(40, 30, None);
+
+    g.paint(&Circle::new([20.0, 15.0], 5.0), &dielectric);
+    typed.paint(&Circle::new([20.0, 15.0], 5.0), &dielectric);
+    g.set_material([5, 5, 0], &dielectric);
+    typed.set_material([5, 5, 0], &dielectric);
+    *g.get_mut(Field::Ez, [10, 10, 0]).unwrap() = 1.0;
+    *typed.get_mut(Field::Ez, [10, 10, 0]).unwrap() = 1.0;
+    typed.view_mut(Field::Hx).unwrap()[[10, 12, 0]] = 0.5;
+    g.view_mut(Field::Hx).unwrap()[[10, 12, 0]] = 0.5;
+    assert!(typed.view_mut(Field::Hz).is_none());
+
+    run(
+        Some(GridDimension::Two(Polarization::Magnetic)),
+        &mut g,
+        Field::Ez,
+        [20, 15, 0],
+    );
+    run(None, &mut typed, Field::Ez, [20, 15, 0]);
+    assert_eq!(&g, typed.grid());
+}
+
+/// Only the components a grid updates are serialized.
+#[test]
+fn test_serialize() {
+    let g = Grid2dTm::new(3, 2, None);
+    let json: serde_json::Value = serde_json::to_value(&g).unwrap();
+    assert_eq!(json, serde_json::to_value(g.grid()).unwrap());
+
+    let keys: Vec<&str> = json
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(|k| k.as_str())
+        .collect();
+    for key in ["x_sz", "y_sz", "z_sz", "cdtds", "hx", "chye", "ez", "cezh"] {
+        assert!(keys.contains(&key), "{}", key);
+    }
+    for key in ["hz", "chzh", "ex", "cexe", "ey", "ceyh"] {
+        assert!(!keys.contains(&key), "{}", key);
+    }
+    assert_eq!(json["ez"].as_array().unwrap().len(), 6);
+}
@@ -0,0 +1,146 @@
+// tests/material.rs
+/// Check the update coefficients computed from materials.
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension};
+use fdtd::grid::{Grid, IMP0};
+use fdtd::material::Material;
+
+/// Vacuum must reproduce the coefficients every grid starts with.
+#[test]
+fn test_vacuum() {
+    for g in [
+        Grid::new_1d(10),
+        Grid::new_2d(10, 8, None),
+        Grid::new_2d_te(10, 8, None),
+        Grid::new_3d(6, 7, 8, None),
+        Grid::new_3d(6, 7, 8, Some(0.5)),
+    ] {
+        let mut h = g.clone();
+        h.set_materials(|_| Material::VACUUM);
+
+        for (a, b) in [
+            (&g.cexe, &h.cexe),
+            (&g.cexh, &h.cexh),
+            (&g.ceye, &h.ceye),
+            (&g.ceyh, &h.ceyh),
+            (&g.ceze, &h.ceze),
+            (&g.cezh, &h.cezh),
+            (&g.chxh, &h.chxh),
+            (&g.chxe, &h.chxe),
+            (&g.chyh, &h.chyh),
+            (&g.chye, &h.chye),
+            (&g.chzh, &h.chzh),
+            (&g.chze, &h.chze),
+        ] {
+            assert_eq!(a.len(), b.len());
+            for (x, y) in a.iter().zip(b) {
+                assert!((x - y).abs() <= 1e-12 * x.abs());
+            }
+        }
+    }
+}
+
+/// The coefficients of a matched lossy layer, as written by hand in
+/// 'Program 3.8'.
+#[test]
+fn test_matched_layer() {
+    let loss = 0.02;
+    let m = Material::matched(9.0, 1.0, 2.0 * 9.0 * loss / IMP0);
+
+    let mut g = Grid::new_1d(20);
+    g.set_material([5, 0, 0], &m);
+
+    let close = |a: f64, b: f64| assert!((a - b).abs() <= 1e-12 * b.abs(), "{} != {}", a, b);
+    close(g.ceze[5], (1.0 - loss) / (1.0 + loss));
+    close(g.cezh[5], IMP0 / 9.0 / (1.0 + loss));
+    close(g.chyh[5], (1.0 - loss) / (1.0 + loss));
+    close(g.chye[5], 1.0 / IMP0 / (1.0 + loss));
+
+    // Neighbouring cells are left alone.
+    assert_eq!(g.ceze[4], 1.0);
+    assert_eq!(g.chye[6], 1.0 / IMP0);
+}
+
+/// A 3D cell sets all of its components, scaled by the Courant number.
+#[test]
+fn test_3d_cell() {
+    let m = Material::new(4.0, 2.0, 0.0, 0.0);
+
+    let mut g = Grid::new_3d(4, 5, 6, None);
+    g.set_material([1, 2, 3], &m);
+
+    let i = (5 + 2) * 6 + 3;
+    for c in [&g.cexh, &g.ceyh, &g.cezh] {
+        assert_eq!(c[i], g.cdtds * IMP0 / 4.0);
+    }
+    for c in [&g.chxe, &g.chye, &g.chze] {
+        assert_eq!(c[i], g.cdtds / IMP0 / 2.0);
+    }
+}
+
+/// Return the largest field reflected back into a dielectric by a layer of
+/// 'layer', along with the fraction of the transmitted pulse left within the layer
+/// halfway through it.
+fn reflection(dielectric: Material, layer: Material) -> (f64, f64) {
+    // Compare against a grid filled with the dielectric, which has nothing
+    // to reflect from until long after the simulation ends.
+    let mut g = Grid::new_1d(400);
+    g.set_materials(|[x, _, _]| if x < 200 { dielectric } else { layer });
+    let mut reference = Grid::new_1d(400);
+    reference.set_materials(|_| dielectric);
+
+    for x in 0..400 {
+        g.ez[x] = (-((x as f64 - 100.0) / 10.0).powi(2)).exp();
+    }
+    reference.ez = g.ez.clone();
+
+    let mut sim: FDTDSim<fn(usize, &mut Grid), fn(usize, &mut Grid)> = FDTDSim::new(
+        Some(GridDimension::One),
+        Some(Backend::Native),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    let mut sim_ref: FDTDSim<fn(usize, &mut Grid), fn(usize, &mut Grid)> = FDTDSim::new(
+        Some(GridDimension::One),
+        Some(Backend::Native),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let peak = |v: &[f64]| v.iter().fold(0.0f64, |a, v| a.max(v.abs()));
+    let (mut reflected, mut absorbed) = (0.0f64, 0.0);
+    for q in 0..600 {
+        sim.step(&mut g).unwrap();
+        sim_ref.step(&mut reference).unwrap();
+
+        for x in 0..200 {
+            reflected = reflected.max((g.ez[x] - reference.ez[x]).abs());
+        }
+
+        // Halfway into the layer, before reaching the end of the grid.
+        if q == 400 {
+            absorbed = peak(&g.ez[200..]) / peak(&reference.ez[200..]);
+        }
+    }
+
+    (reflected, absorbed)
+}
+
+/// A matched layer has the impedance of the lossless material, so a wave
+/// enters it with little reflection, unlike an electrically lossy layer.
+#[test]
+fn test_matched_impedance() {
+    let dielectric = Material::dielectric(4.0);
+    let sigma = 2.0 * 4.0 * 0.01 / IMP0;
+
+    let (matched, absorbed) = reflection(dielectric, Material::matched(4.0, 1.0, sigma));
+    let (lossy, _) = reflection(dielectric, Material::new(4.0, 1.0, sigma, 0.0));
+
+    // Half of the pulse heads right; almost all of it is absorbed.
+    assert!(matched < 0.01, "{}", matched);
+    assert!(lossy > 5.0 * matched, "{} {}", lossy, matched);
+    assert!(absorbed < 0.1, "{}", absorbed);
+}
@@ -0,0 +1,125 @@
+// tests/monitor.rs
+/// Check running DFT monitors.
+use fdtd::fdtd::{GridDimension, Polarization};
+use fdtd::grid::{Field, Grid};
+use fdtd::monitor::{DftMonitor, Region};
+use fdtd::sources::PointSource;
+use fdtd::waveform::{Gaussian, Sinusoid};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+mod util;
+use util::sim::sim;
+
+/// Regions list their nodes in the order of the field vectors, ignoring
+/// axes the grid doesn't have and clipping to the grid.
+#[test]
+fn test_regions() {
+    let g = Grid::new_2d(10, 8, None);
+    assert_eq!(Region::point([3, 4, 0]).nodes(&g), vec![[3, 4, 0]]);
+    assert_eq!(
+        Region::line([2, 5, 0], 0, 3).nodes(&g),
+        vec![[2, 5, 0], [3, 5, 0], [4, 5, 0]]
+    );
+
+    let plane = Region::plane(&g, 1, 2).nodes(&g);
+    assert_eq!(plane.len(), 10);
+    assert!(plane.iter().all(|p| p[1] == 2 && p[2] == 0));
+    assert_eq!(Region::all(&g).nodes(&g).len(), 80);
+    assert_eq!(Region::new([8, 0, 0], [20, 1, 1]).nodes(&g).len(), 2);
+
+    let g = Grid::new_3d(4, 5, 6, None);
+    let volume = Region::new([1, 1, 1], [3, 3, 3]).nodes(&g);
+    assert_eq!(volume.len(), 8);
+    assert_eq!(volume[1], [1, 1, 2]);
+}
+
+/// The phasors match a DFT of the fields recorded by hand, taking the
+/// magnetic field half a step before the electric field.
+#[test]
+fn test_matches_recorded() {
+    let (x_sz, y_sz) = (60, 40);
+    let omegas = [0.1, 0.25];
+    let mut g = Grid::new_2d(x_sz, y_sz, None);
+    let line = Region::line([30, 5, 0], 1, 20);
+    let monitor = Rc::new(RefCell::new(DftMonitor::new(
+        &g,
+        &[Field::Ez, Field::Hx, Field::Hz],
+        line,
+        &omegas,
+    )));
+
+    let mut s = sim(GridDimension::Two(Polarization::Magnetic));
+    s.add_source(PointSource::soft(
+        Field::Ez,
+        [20, 20, 0],
+        Gaussian::new(20.0, 6.0),
+    ));
+    s.add_observer(monitor.clone());
+
+    let (mut ez, mut hx) = (Vec::new(), Vec::new());
+    for _ in 0..120 {
+        s.step(&mut g).unwrap();
+        ez.push(g.ez[30 * y_sz + 12]);
+        hx.push(g.hx[30 * y_sz + 12]);
+    }
+
+    let dft = |v: &[f64], omega: f64, delay: f64| {
+        v.iter().enumerate().fold((0.0, 0.0), |acc, (t, v)| {
+            let (sin, cos) = (omega * (t as f64 + 1.0 - delay)).sin_cos();
+            (acc.0 + v * cos, acc.1 - v * sin)
+        })
+    };
+
+    let m = monitor.borrow();
+    assert_eq!(m.fields(), &[Field::Ez, Field::Hx]);
+    assert_eq!(m.steps(), 120);
+    assert!(m.phasors(Field::Hz, 0).is_none());
+    for (i, &omega) in omegas.iter().enumerate() {
+        let expected = [
+            (Field::Ez, dft(&ez, omega, 0.0)),
+            (Field::Hx, dft(&hx, omega, 0.5)),
+        ];
+        for (f, e) in expected {
+            let p = m.phasor(f, i, [30, 12, 0]).unwrap();
+            assert!((p.0 - e.0).abs() < 1e-12 && (p.1 - e.1).abs() < 1e-12);
+            assert!(p.0.abs() + p.1.abs() > 1e-6);
+        }
+    }
+}
+
+/// A monitor is written as JSON, with phasors as (real, imaginary) pairs.
+#[test]
+fn test_write() {
+    let mut g = Grid::new_1d(100);
+    let monitor = Rc::new(RefCell::new(DftMonitor::new(
+        &g,
+        &[Field::Ez],
+        Region::line([40, 0, 0], 0, 5),
+        &[0.3],
+    )));
+
+    let mut s = sim(GridDimension::One);
+    s.add_source(PointSource::soft(
+        Field::Ez,
+        [50, 0, 0],
+        Sinusoid::new(0.3 / (2.0 * std::f64::consts::PI), 10.0),
+    ));
+    s.add_observer(monitor.clone());
+    s.step_mul(&mut g, 60).unwrap();
+
+    let path = std::env::temp_dir().join("fdtd_monitor_test.json");
+    let path = path.to_str().unwrap();
+    monitor.borrow().write(path).unwrap();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(json["fields"][0], "Ez");
+    assert_eq!(json["nodes"].as_array().unwrap().len(), 5);
+    let p = &json["phasors"][0][0][2];
+    let (re, im) = (p[0].as_f64().unwrap(), p[1].as_f64().unwrap());
+    let expected = monitor.borrow().phasor(Field::Ez, 0, [42, 0, 0]).unwrap();
+    assert_eq!((re, im), expected);
+}
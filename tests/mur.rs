@@ -0,0 +1,149 @@
+// tests/mur.rs
+/// Compare Mur-terminated grids against grids large enough that no
+/// reflection returns within the run.
+use fdtd::abc;
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension, Polarization};
+use fdtd::grid::Grid;
+use fdtd::waveform::{DiffGaussian, Waveform};
+
+mod util;
+
+/// Record a field at 'probe' for 'steps' steps while exciting 'center' with
+/// a soft source. TM^z grids and 3D grids are driven through Ez, TE^z grids
+/// through Hz.
+fn run<F>(
+    g: &mut Grid,
+    dimension: GridDimension,
+    (center, probe): (usize, usize),
+    steps: usize,
+    abc: F,
+) -> Vec<f64>
+where
+    F: FnMut(usize, &mut Grid),
+{
+    let is_te = matches!(dimension, GridDimension::Two(Polarization::Electric));
+    let mut abc = abc;
+
+    // Mur ABCs don't absorb the static field left behind by a pulse with a
+    // DC component.
+    let pulse = DiffGaussian::new(30.0, 10.0);
+
+    let post_magnetic = |t: usize, g: &mut Grid| {
+        if is_te {
+            g.hz[center] += pulse.value(t as f64);
+        }
+    };
+
+    let post_electric = |t: usize, g: &mut Grid| {
+        abc(t, g);
+        if !is_te {
+            g.ez[center] += pulse.value(t as f64);
+        }
+    };
+
+    let mut sim = FDTDSim::new(
+        Some(dimension),
+        Some(Backend::Native),
+        Some(post_magnetic),
+        Some(post_electric),
+        None,
+    )
+    .unwrap();
+
+    let mut res = Vec::with_capacity(steps);
+    for _ in 0..steps {
+        sim.step(g).unwrap();
+        res.push(if is_te { g.hz[probe] } else { g.ez[probe] });
+    }
+
+    res
+}
+
+/// Check PEC-, 1st order- and 2nd order-terminated grids against a larger
+/// reference grid, returning the errors of the 1st and 2nd order ABCs.
+/// 'build' returns a grid of the given size along with its source and probe
+/// indices.
+fn check(
+    build: impl Fn(usize) -> (Grid, (usize, usize)),
+    dimension: GridDimension,
+    sizes: [usize; 2],
+    steps: usize,
+) -> (f64, f64) {
+    let nop = |_: usize, _: &mut Grid| {};
+    let [small, large] = sizes;
+
+    let (mut g, at) = build(large);
+    let reference = run(&mut g, dimension, at, steps, nop);
+
+    let (mut g, at) = build(small);
+    let pec = run(&mut g, dimension, at, steps, nop);
+
+    let (mut g, at) = build(small);
+    let first = abc::mur_abc_1st_order(&g, dimension);
+    let first = run(&mut g, dimension, at, steps, first);
+
+    let (mut g, at) = build(small);
+    let second = abc::mur_abc_2nd_order(&g, dimension);
+    let second = run(&mut g, dimension, at, steps, second);
+
+    let [pec, first, second] =
+        [pec, first, second].map(|r| util::record::relative_error(&r, &reference));
+    assert!(pec > 0.5);
+    assert!(first < 0.1);
+    assert!(second <= first);
+
+    (first, second)
+}
+
+fn check_2d(
+    new: fn(usize, usize, Option<f64>) -> Grid,
+    dimension: GridDimension,
+    steps: usize,
+) -> (f64, f64) {
+    // Probe close to the low x face, off the axes of symmetry.
+    let build = |sz: usize| {
+        let center = (sz / 2) * sz + sz / 2;
+        let probe = (sz / 2 - 18) * sz + sz / 2 + 7;
+        (new(sz, sz, None), (center, probe))
+    };
+
+    check(build, dimension, [44, 200], steps)
+}
+
+#[test]
+fn test_mur_tm() {
+    let (first, second) = check_2d(
+        Grid::new_2d,
+        GridDimension::Two(Polarization::Magnetic),
+        120,
+    );
+    assert!(second < 0.6 * first);
+}
+
+#[test]
+fn test_mur_te() {
+    // The row ends next to the corners are only closed to 1st order, and
+    // reflect more than the 1st order ABC does there. Stop before those
+    // reflections reach the probe.
+    let (first, second) = check_2d(
+        Grid::new_2d_te,
+        GridDimension::Two(Polarization::Electric),
+        80,
+    );
+    assert!(second < 0.6 * first);
+}
+
+#[test]
+fn test_mur_3d() {
+    let build = |sz: usize| {
+        let c = sz / 2;
+        let idx = |x: usize, y: usize, z: usize| (x * sz + y) * sz + z;
+        let at = (idx(c, c, c), idx(c - 8, c + 3, c + 2));
+        (Grid::new_3d(sz, sz, sz, None), at)
+    };
+
+    // As for TE^z, stop before the reflections off the edges of the faces
+    // reach the probe.
+    let (first, second) = check(build, GridDimension::Three, [24, 64], 70);
+    assert!(second < 0.6 * first);
+}
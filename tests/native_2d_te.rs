@@ -0,0 +1,100 @@
+// tests/native_2d_te.rs
+/// Sanity checks for the native TE^z stepping code.
+use fdtd::error;
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension, Polarization};
+use fdtd::grid::Grid;
+
+const SIZE: usize = 21;
+const CENTER: usize = SIZE / 2;
+
+/// Return a simulation that *should* call the native backend.
+fn setup_step_native(
+) -> Result<FDTDSim<impl FnMut(usize, &mut Grid), impl FnMut(usize, &mut Grid)>, error::FDTDError> {
+    let post_magnetic = |_t: usize, _g: &mut Grid| {};
+    let post_electric = |_t: usize, _g: &mut Grid| {};
+
+    let mut res = FDTDSim::new(
+        Some(GridDimension::Two(Polarization::Electric)),
+        Some(Backend::Native),
+        Some(post_magnetic),
+        Some(post_electric),
+        None,
+    )?;
+
+    res.set_post_magnetic(None);
+    res.set_post_electric(None);
+
+    Ok(res)
+}
+
+#[test]
+fn test_default_grid() {
+    let mut grid = Grid::new_2d_te(SIZE, SIZE, None);
+    let mut sim = setup_step_native().unwrap();
+
+    assert_eq!(sim.step_mul(&mut grid, 100).is_ok(), true);
+    assert_eq!(grid, Grid::new_2d_te(SIZE, SIZE, None));
+}
+
+#[test]
+fn test_single_step() {
+    let mut grid = Grid::new_2d_te(SIZE, SIZE, None);
+    let mut sim = setup_step_native().unwrap();
+
+    let c = CENTER * SIZE + CENTER;
+    grid.hz[c] = 1.0;
+    assert_eq!(sim.step(&mut grid).is_ok(), true);
+
+    // With no electric field, hz is left untouched; the curl of hz then
+    // drives the four electric components surrounding it.
+    assert_eq!(grid.hz[c], 1.0);
+    assert_eq!(grid.ex[c], grid.cexh[c]);
+    assert_eq!(grid.ex[c + 1], -grid.cexh[c + 1]);
+    assert_eq!(grid.ey[c], -grid.ceyh[c]);
+    assert_eq!(grid.ey[c + SIZE], grid.ceyh[c + SIZE]);
+
+    let nonzero = grid.ex.iter().chain(grid.ey.iter()).filter(|v| **v != 0.0);
+    assert_eq!(nonzero.count(), 4);
+}
+
+#[test]
+fn test_symmetry() {
+    let mut grid = Grid::new_2d_te(SIZE, SIZE, None);
+    let mut sim = setup_step_native().unwrap();
+
+    grid.hz[CENTER * SIZE + CENTER] = 1.0;
+    assert_eq!(sim.step_mul(&mut grid, CENTER / 2).is_ok(), true);
+
+    // Before reaching the walls, a pulse from the center of a square grid
+    // should be symmetric about both axes and the diagonal.
+    for a in 0..CENTER {
+        for b in 0..CENTER {
+            let v = grid.hz[(CENTER + a) * SIZE + (CENTER + b)];
+            let mirrored = [
+                grid.hz[(CENTER - a) * SIZE + (CENTER + b)],
+                grid.hz[(CENTER + a) * SIZE + (CENTER - b)],
+                grid.hz[(CENTER + b) * SIZE + (CENTER + a)],
+            ];
+
+            for m in mirrored {
+                assert!((v - m).abs() < 1e-12);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_pec_walls() {
+    let mut grid = Grid::new_2d_te(SIZE, SIZE, None);
+    let mut sim = setup_step_native().unwrap();
+
+    grid.hz[3 * SIZE + 4] = 1.0;
+    assert_eq!(sim.step_mul(&mut grid, 200).is_ok(), true);
+
+    // Tangential electric fields along the m = 0 and n = 0 walls are never
+    // updated.
+    for i in 0..SIZE {
+        assert_eq!(grid.ex[i * SIZE], 0.0);
+        assert_eq!(grid.ey[i], 0.0);
+    }
+}
@@ -0,0 +1,287 @@
+// tests/ntff.rs
+/// Check the near-to-far-field transformation against the fields of line
+/// and point currents, and the scattering of a PEC cylinder.
+use fdtd::conductor::{Conductor, Conductors};
+use fdtd::cpml::{Cpml, CpmlParams};
+use fdtd::dft::Complex;
+use fdtd::error::FDTDError;
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension, Polarization};
+use fdtd::geometry::Circle;
+use fdtd::grid::{Field, Grid};
+use fdtd::ntff::{NtffFrequency, NtffTime};
+use fdtd::sources::PointSource;
+use fdtd::tfsf::Tfsf2d;
+use fdtd::waveform::{DiffGaussian, Ricker};
+use std::cell::RefCell;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+mod util;
+use util::sim::sim;
+
+fn magnitude(c: Complex) -> f64 {
+    (c.0 * c.0 + c.1 * c.1).sqrt()
+}
+
+/// DFT of a far-field series at 'omega'.
+fn dft(start: f64, v: &[f64], omega: f64) -> Complex {
+    v.iter().enumerate().fold((0.0, 0.0), |acc, (k, v)| {
+        let (sin, cos) = (omega * (start + k as f64)).sin_cos();
+        (acc.0 + v * cos, acc.1 - v * sin)
+    })
+}
+
+/// Run a current source at the centre of 'g', recording the NTFF on a box
+/// 'half' cells either side of it, and return the observers along with the
+/// DFT of 'Ez' at 'probe'.
+fn run(
+    mut g: Grid,
+    dimension: GridDimension,
+    half: usize,
+    omega: f64,
+    directions: &[(f64, f64)],
+    probe: [usize; 3],
+    steps: usize,
+) -> (NtffFrequency, NtffTime, Complex) {
+    let used = [true, g.y_sz > 0, g.z_sz > 0];
+    let sz = [g.x_sz, g.y_sz, g.z_sz];
+    let center = [0, 1, 2].map(|a| if used[a] { sz[a] / 2 } else { 0 });
+    let min = [0, 1, 2].map(|a| if used[a] { center[a] - half } else { 0 });
+    let max = [0, 1, 2].map(|a| if used[a] { center[a] + half } else { 0 });
+
+    let frequency = Rc::new(RefCell::new(
+        NtffFrequency::new(&g, min, max, &[omega]).unwrap(),
+    ));
+    let time = Rc::new(RefCell::new(
+        NtffTime::new(&g, min, max, directions).unwrap(),
+    ));
+
+    let mut s = sim(dimension);
+    s.set_cpml(Some(Cpml::new(&g, dimension, &CpmlParams::default())))
+        .unwrap();
+    s.add_source(PointSource::current(
+        Field::Ez,
+        center,
+        DiffGaussian::new(40.0, 10.0),
+    ));
+    s.add_observer(frequency.clone());
+    s.add_observer(time.clone());
+
+    let i = (probe[0] * g.y_sz.max(1) + probe[1]) * g.z_sz.max(1) + probe[2];
+    let mut near = (0.0, 0.0);
+    for t in 1..=steps {
+        s.step(&mut g).unwrap();
+        let (sin, cos) = (omega * t as f64).sin_cos();
+        near = (near.0 + g.ez[i] * cos, near.1 - g.ez[i] * sin);
+    }
+
+    drop(s);
+    let frequency = Rc::try_unwrap(frequency).unwrap().into_inner();
+    let time = Rc::try_unwrap(time).unwrap().into_inner();
+    (frequency, time, near)
+}
+
+/// A line current radiates evenly in every direction, and its far field
+/// matches the field sampled well away from it. The time-domain far field
+/// matches the frequency-domain one.
+#[test]
+fn test_line_current_2d() {
+    let size = 160;
+    let omega = 2.0 * PI / 2.0f64.sqrt() / 20.0;
+    let directions = [(0.0, 0.0), (0.0, PI / 4.0), (0.0, PI / 2.0)];
+    let (frequency, time, near) = run(
+        Grid::new_2d(size, size, None),
+        GridDimension::Two(Polarization::Magnetic),
+        20,
+        omega,
+        &directions,
+        [size / 2 + 60, size / 2, 0],
+        700,
+    );
+
+    // E_θ = -E_z within the plane.
+    let far: Vec<Complex> = directions
+        .iter()
+        .map(|&(theta, phi)| frequency.far_field(0, theta, phi).0)
+        .collect();
+    for f in &far {
+        assert!(
+            (magnitude(*f) / magnitude(far[0]) - 1.0).abs() < 0.02,
+            "{:?}",
+            far
+        );
+    }
+
+    let expected = 60.0f64.sqrt() * magnitude(near);
+    assert!(
+        (magnitude(far[0]) / expected - 1.0).abs() < 0.05,
+        "{} {}",
+        magnitude(far[0]),
+        expected
+    );
+
+    for (d, f) in far.iter().enumerate() {
+        let series = time.far_field(d);
+        let transformed = magnitude(dft(series.start, &series.e_theta, omega));
+        assert!(
+            (transformed / magnitude(*f) - 1.0).abs() < 0.03,
+            "{} {}",
+            transformed,
+            magnitude(*f)
+        );
+    }
+}
+
+/// A short current element radiates 'E_θ ∝ sin θ', with no 'E_φ'.
+#[test]
+fn test_dipole_3d() {
+    let size = 40;
+    let omega = 2.0 * PI / 3.0f64.sqrt() / 16.0;
+    let directions = [(PI / 2.0, 0.0), (PI / 3.0, 0.3), (PI / 6.0, 1.2)];
+    let (frequency, time, _) = run(
+        Grid::new_3d(size, size, size, None),
+        GridDimension::Three,
+        6,
+        omega,
+        &directions,
+        [0, 0, 0],
+        160,
+    );
+
+    let broadside = magnitude(frequency.far_field(0, PI / 2.0, 0.0).0);
+    for &(theta, phi) in &directions {
+        let (e_theta, e_phi) = frequency.far_field(0, theta, phi);
+        let ratio = magnitude(e_theta) / broadside;
+        assert!((ratio - theta.sin()).abs() < 0.03, "{} {}", theta, ratio);
+        assert!(magnitude(e_phi) < 0.01 * broadside);
+    }
+
+    for (d, &(theta, phi)) in directions.iter().enumerate() {
+        let series = time.far_field(d);
+        let transformed = magnitude(dft(series.start, &series.e_theta, omega));
+        let expected = magnitude(frequency.far_field(0, theta, phi).0);
+        assert!(
+            (transformed / expected - 1.0).abs() < 0.03,
+            "{} {}",
+            transformed,
+            expected
+        );
+    }
+}
+
+/// Bessel functions of the first and second kind of orders '0..n' at 'x',
+/// from their series for orders 0 and 1 and upward recurrence for 'Y'.
+fn bessel(n: usize, x: f64) -> (Vec<f64>, Vec<f64>) {
+    let q = x * x / 4.0;
+    let j: Vec<f64> = (0..n)
+        .map(|order| {
+            let (mut term, mut sum) = ((x / 2.0).powi(order as i32), 0.0);
+            term /= (1..=order).map(|k| k as f64).product::<f64>();
+            for k in 0..40 {
+                sum += term;
+                term *= -q / ((k + 1) * (k + 1 + order)) as f64;
+            }
+            sum
+        })
+        .collect();
+
+    // Y_0 from its series, and Y_1 from the Wronskian.
+    const EULER: f64 = 0.577_215_664_901_532_9;
+    let (mut term, mut harmonic, mut sum) = (1.0, 0.0, 0.0);
+    for k in 1..40 {
+        term *= -q / (k * k) as f64;
+        harmonic += 1.0 / k as f64;
+        sum -= term * harmonic;
+    }
+    let mut y = vec![2.0 / PI * (((x / 2.0).ln() + EULER) * j[0] + sum)];
+    y.push((j[1] * y[0] - 2.0 / (PI * x)) / j[0]);
+    for order in 1..n - 1 {
+        y.push(2.0 * order as f64 / x * y[order] - y[order - 1]);
+    }
+
+    (j, y)
+}
+
+/// Echo width of a PEC cylinder of radius 'a' lit by a TM^z plane wave
+/// travelling in +x, toward 'phi', with 'k' the wavenumber.
+fn echo_width(k: f64, a: f64, phi: f64) -> f64 {
+    let (j, y) = bessel(20, k * a);
+    let sum = (0..20).fold((0.0, 0.0), |acc, n| {
+        // J_n / H_n^(2), with H_n^(2) = J_n - i Y_n; orders -n and n match.
+        let d = j[n] * j[n] + y[n] * y[n];
+        let c = if n == 0 { 1.0 } else { 2.0 } * (n as f64 * phi).cos();
+        (acc.0 + c * j[n] * j[n] / d, acc.1 + c * j[n] * y[n] / d)
+    });
+    4.0 / k * (sum.0 * sum.0 + sum.1 * sum.1)
+}
+
+/// The echo width of a PEC cylinder matches the analytic series.
+#[test]
+fn test_cylinder_echo_width() {
+    let size = 100;
+    let radius = 8.0;
+    let ppw = 20.0;
+    let directions = [0.0, PI / 2.0, 3.0 * PI / 4.0, PI];
+
+    let mut g = Grid::new_2d(size, size, None);
+    let dimension = GridDimension::Two(Polarization::Magnetic);
+    let center = size as f64 / 2.0;
+    let mut conductors = Conductors::new(&g);
+    conductors.add(&g, &Circle::new([center, center], radius), Conductor::Pec);
+
+    let omega = 2.0 * PI * g.cdtds / ppw;
+    let ricker = Ricker::new(g.cdtds, ppw, 0.0);
+    let tfsf = Tfsf2d::new(&g, (37, 37), (63, 63), ricker).unwrap();
+    let frequency = Rc::new(RefCell::new(
+        NtffFrequency::new(&g, [30, 30, 0], [70, 70, 0], &[omega]).unwrap(),
+    ));
+
+    let post_magnetic = |t: usize, g: &mut Grid| tfsf.magnetic(t, g);
+    let post_electric = |t: usize, g: &mut Grid| tfsf.electric(t, g);
+    let mut s = FDTDSim::new(
+        Some(dimension),
+        Some(Backend::Native),
+        Some(post_magnetic),
+        Some(post_electric),
+        None,
+    )
+    .unwrap();
+    s.set_cpml(Some(Cpml::new(&g, dimension, &CpmlParams::default())))
+        .unwrap();
+    s.set_conductors(Some(conductors));
+    s.add_observer(frequency.clone());
+    s.step_mul(&mut g, 800).unwrap();
+
+    let frequency = frequency.borrow();
+    let incident = frequency.incident(0, &ricker);
+    for phi in directions {
+        let width = frequency.rcs(0, PI / 2.0, phi, incident);
+        let expected = echo_width(2.0 * PI / ppw, radius, phi);
+        assert!(
+            (width / expected - 1.0).abs() < 0.1,
+            "{} {} {}",
+            phi,
+            width,
+            expected
+        );
+    }
+}
+
+/// A Huygens box needs a 2D or 3D grid, and must fit within it.
+#[test]
+fn test_region() {
+    let g = Grid::new_1d(40);
+    assert!(matches!(
+        NtffFrequency::new(&g, [10, 0, 0], [30, 0, 0], &[0.1]),
+        Err(FDTDError::Region { .. })
+    ));
+
+    let g = Grid::new_2d(40, 40, None);
+    for (min, max) in [([10, 10, 0], [40, 30, 0]), ([20, 10, 0], [20, 30, 0])] {
+        assert!(matches!(
+            NtffTime::new(&g, min, max, &[(0.0, 0.0)]),
+            Err(FDTDError::Region { .. })
+        ));
+    }
+    assert!(NtffTime::new(&g, [10, 10, 0], [30, 30, 0], &[(0.0, 0.0)]).is_ok());
+}
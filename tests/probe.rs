@@ -0,0 +1,145 @@
+// tests/probe.rs
+/// Check time-series probes.
+use fdtd::error::FDTDError;
+use fdtd::fdtd::{GridDimension, Polarization};
+use fdtd::grid::{Field, Grid};
+use fdtd::probe::{Location, Probe};
+use fdtd::sources::PointSource;
+use fdtd::waveform::Gaussian;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+mod util;
+use util::sim::sim;
+
+/// A probe records the fields at its nodes every 'interval' steps, and
+/// interpolates between nodes at other positions.
+#[test]
+fn test_matches_recorded() {
+    let (x_sz, y_sz) = (50, 40);
+    let mut g = Grid::new_2d(x_sz, y_sz, None);
+    let locations = [
+        Location::Node([30, 12, 0]),
+        Location::Position([30.0, 12.25, 0.0]),
+    ];
+    let probe = Rc::new(RefCell::new(
+        Probe::new(&g, &[Field::Ez, Field::Hx, Field::Hz], &locations, 3).unwrap(),
+    ));
+
+    let mut s = sim(GridDimension::Two(Polarization::Magnetic));
+    s.add_source(PointSource::soft(
+        Field::Ez,
+        [20, 20, 0],
+        Gaussian::new(20.0, 6.0),
+    ));
+    s.add_observer(probe.clone());
+
+    let (mut ez, mut hx, mut between) = (Vec::new(), Vec::new(), Vec::new());
+    for t in 1..=100 {
+        s.step(&mut g).unwrap();
+        if t % 3 == 0 {
+            let i = 30 * y_sz + 12;
+            ez.push(g.ez[i]);
+            hx.push(g.hx[i]);
+            between.push(0.75 * g.ez[i] + 0.25 * g.ez[i + 1]);
+        }
+    }
+
+    let p = probe.borrow();
+    assert_eq!(p.fields(), &[Field::Ez, Field::Hx]);
+    assert_eq!(p.times(), (1..=33).map(|k| 3 * k).collect::<Vec<_>>());
+    assert_eq!(p.series(Field::Ez, 0).unwrap(), ez);
+    assert_eq!(p.series(Field::Hx, 0).unwrap(), hx);
+    assert!(p.series(Field::Hz, 0).is_none());
+    assert!(p.series(Field::Ez, 2).is_none());
+    assert!(ez.iter().any(|v| v.abs() > 1e-6));
+
+    for (a, b) in p.series(Field::Ez, 1).unwrap().iter().zip(&between) {
+        assert!((a - b).abs() < 1e-15);
+    }
+}
+
+/// Recordings are written as CSV, a row per step, and as JSON, a series per
+/// location and component.
+#[test]
+fn test_write() {
+    let mut g = Grid::new_1d(100);
+    let locations = [Location::Node([40, 0, 0]), Location::Node([60, 0, 0])];
+    let probe = Rc::new(RefCell::new(
+        Probe::new(&g, &[Field::Ez, Field::Hy], &locations, 5).unwrap(),
+    ));
+    probe.borrow_mut().reserve(60);
+
+    let mut s = sim(GridDimension::One);
+    s.add_source(PointSource::soft(
+        Field::Ez,
+        [50, 0, 0],
+        Gaussian::new(20.0, 5.0),
+    ));
+    s.add_observer(probe.clone());
+    s.step_mul(&mut g, 60).unwrap();
+
+    let p = probe.borrow();
+    let csv = p.to_csv();
+    let rows: Vec<Vec<&str>> = csv.lines().map(|l| l.split(',').collect()).collect();
+    assert_eq!(
+        rows[0],
+        [
+            "step",
+            "Ez(40 0 0)",
+            "Hy(40 0 0)",
+            "Ez(60 0 0)",
+            "Hy(60 0 0)"
+        ]
+    );
+    assert_eq!(rows.len(), 13);
+    assert_eq!(rows[4][0], "20");
+    let hy = p.series(Field::Hy, 1).unwrap();
+    assert_eq!(rows[4][4].parse::<f64>().unwrap(), hy[3]);
+
+    let path = std::env::temp_dir().join("fdtd_probe_test.json");
+    let path = path.to_str().unwrap();
+    p.write_json(path).unwrap();
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(json["fields"][1], "Hy");
+    assert_eq!(json["locations"][1]["Node"][0], 60);
+    assert_eq!(json["times"].as_array().unwrap().len(), 12);
+    let series: Vec<f64> = json["series"][1][1]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v.as_f64().unwrap())
+        .collect();
+    for (a, b) in series.iter().zip(&hy) {
+        assert!((a - b).abs() <= 1e-15 * b.abs());
+    }
+}
+
+/// Nodes beyond the grid are rejected, for the components recorded.
+#[test]
+fn test_out_of_bounds() {
+    let g = Grid::new_2d(50, 40, None);
+    assert!(matches!(
+        Probe::node(&g, &[Field::Ez], [50, 10, 0]),
+        Err(FDTDError::Node {
+            field: Field::Ez,
+            pos: [50, 10, 0]
+        })
+    ));
+    assert!(Probe::node(&g, &[Field::Ez], [49, 39, 0]).is_ok());
+    assert!(Probe::node(&g, &[Field::Ez], [10, 10, 1]).is_err());
+}
+
+/// Probes record at least every step.
+#[test]
+fn test_zero_interval() {
+    let g = Grid::new_2d(50, 40, None);
+    let locations = [Location::Node([10, 10, 0])];
+    assert!(matches!(
+        Probe::new(&g, &[Field::Ez], &locations, 0),
+        Err(FDTDError::Interval { interval: 0 })
+    ));
+}
@@ -0,0 +1,114 @@
+// tests/sources.rs
+/// Check that point sources excite their node at the right time and with the
+/// right scaling.
+use fdtd::error::FDTDError;
+use fdtd::fdtd::{GridDimension, Polarization};
+use fdtd::grid::{Field, Grid};
+use fdtd::sources::PointSource;
+use fdtd::waveform::{Gaussian, Ricker, Waveform};
+
+mod util;
+use util::sim::sim;
+
+#[test]
+fn test_hard_source_2d() {
+    let mut g = Grid::new_2d(41, 31, None);
+    let mut s = sim(GridDimension::Two(Polarization::Magnetic));
+
+    let w = Ricker::new(g.cdtds, 20.0, 0.0);
+    s.add_source(PointSource::hard(Field::Ez, [20, 15, 0], w));
+
+    let loc = 20 * 31 + 15;
+    for q in 0..60 {
+        s.step(&mut g).unwrap();
+        assert_eq!(g.ez[loc], w.step(q + 1));
+    }
+
+    // The source radiates.
+    assert!(g.ez[loc + 5].abs() > 0.0);
+}
+
+#[test]
+fn test_soft_source_1d() {
+    let mut g = Grid::new_1d(50);
+    let mut hard = g.clone();
+
+    let w = Gaussian::new(10.0, 4.0);
+
+    let mut s = sim(GridDimension::One);
+    s.add_source(PointSource::soft(Field::Ez, [25, 0, 0], w));
+
+    // A soft source adds to the field; on an empty grid its first step is
+    // indistinguishable from a hard source.
+    s.step(&mut g).unwrap();
+    assert_eq!(g.ez[25], w.step(1));
+
+    // Afterwards, the field left by the previous step is kept.
+    let mut s_hard = sim(GridDimension::One);
+    s_hard.add_source(PointSource::hard(Field::Ez, [25, 0, 0], w));
+    s_hard.step(&mut hard).unwrap();
+
+    let mut expected = hard.clone();
+    s.step(&mut g).unwrap();
+    s_hard.step(&mut hard).unwrap();
+    sim(GridDimension::One).step(&mut expected).unwrap();
+    assert_eq!(g.ez[25], expected.ez[25] + w.step(2));
+    assert_eq!(hard.ez[25], w.step(2));
+}
+
+#[test]
+fn test_current_source_3d() {
+    let mut g = Grid::new_3d(12, 12, 12, None);
+    let w = Gaussian::new(10.0, 4.0);
+
+    let mut s = sim(GridDimension::Three);
+    s.add_source(PointSource::current(Field::Ez, [6, 6, 6], w));
+    s.add_source(PointSource::current(Field::Hx, [3, 4, 5], w));
+
+    // Currents enter the update as the curl does, sampled half a step
+    // before the field they update.
+    s.step(&mut g).unwrap();
+
+    let e = (6 * 12 + 6) * 12 + 6;
+    let h = (3 * 12 + 4) * 12 + 5;
+    assert_eq!(g.hx[h], -g.chxe[h] * w.value(0.0));
+    assert!((g.ez[e] + g.cezh[e] * w.value(0.5)).abs() < 1e-12 * g.cezh[e]);
+}
+
+#[test]
+fn test_magnetic_source_2d_te() {
+    let mut g = Grid::new_2d_te(21, 21, None);
+    let w = Gaussian::new(5.0, 2.0);
+
+    let mut s = sim(GridDimension::Two(Polarization::Electric));
+    s.add_source(PointSource::hard(Field::Hz, [10, 10, 0], w));
+
+    let loc = 10 * 21 + 10;
+    for q in 0..20 {
+        s.step(&mut g).unwrap();
+        assert_eq!(g.hz[loc], w.half_step(q));
+    }
+}
+
+/// Sources are checked against the grid before it is stepped.
+#[test]
+fn test_out_of_bounds() {
+    let mut g = Grid::new_2d(10, 8, None);
+    let nodes = [
+        (Field::Ez, [10, 4, 0]),
+        (Field::Ez, [4, 8, 0]),
+        (Field::Ez, [4, 4, 1]),
+        (Field::Hz, [4, 4, 0]),
+    ];
+
+    for (field, pos) in nodes {
+        let mut s = sim(GridDimension::Two(Polarization::Magnetic));
+        s.add_source(PointSource::soft(field, pos, Gaussian::new(10.0, 4.0)));
+        match s.step(&mut g) {
+            Err(FDTDError::Node { field: f, pos: p }) => assert_eq!((f, p), (field, pos)),
+            r => panic!("{:?}", r),
+        }
+    }
+
+    assert_eq!(g, Grid::new_2d(10, 8, None));
+}
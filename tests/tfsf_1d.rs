@@ -0,0 +1,76 @@
+// tests/tfsf_1d.rs
+use fdtd::error::FDTDError;
+/// Check that a 1D TFSF boundary leaves the scattered-field region empty
+/// when nothing scatters the incident wave.
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension};
+use fdtd::grid::Grid;
+use fdtd::material::Material;
+use fdtd::tfsf::Tfsf1d;
+
+const SIZE: usize = 400;
+const BOUNDARY: usize = 100;
+const STEPS: usize = 200;
+
+/// Return the largest field seen within the scattered- and total-field
+/// regions of a grid with the given Courant number, filled with 'medium'.
+fn run(cdtds: f64, medium: Material) -> (f64, f64) {
+    let mut g = Grid::new_1d(SIZE);
+    g.cdtds = cdtds;
+    g.set_materials(|_| medium);
+
+    let pulse = |t: f64| (-((t - 60.0) / 12.0).powi(2)).exp();
+    let tfsf = Tfsf1d::new(BOUNDARY, pulse, medium).unwrap();
+
+    let post_magnetic = |t: usize, g: &mut Grid| tfsf.magnetic(t, g);
+    let post_electric = |t: usize, g: &mut Grid| tfsf.electric(t, g);
+
+    let mut sim = FDTDSim::new(
+        Some(GridDimension::One),
+        Some(Backend::Native),
+        Some(post_magnetic),
+        Some(post_electric),
+        None,
+    )
+    .unwrap();
+
+    let (mut scattered, mut total) = (0.0f64, 0.0f64);
+    for _ in 0..STEPS {
+        sim.step(&mut g).unwrap();
+
+        let max = |v: &[f64]| v.iter().fold(0.0f64, |acc, v| acc.max(v.abs()));
+        scattered = scattered.max(max(&g.ez[..BOUNDARY]));
+        total = total.max(max(&g.ez[BOUNDARY..]));
+    }
+
+    (scattered, total)
+}
+
+#[test]
+fn test_magic_time_step() {
+    let (scattered, total) = run(1.0, Material::VACUUM);
+    assert!(scattered < 1e-9);
+    assert!((total - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_courant_half() {
+    let (scattered, total) = run(0.5, Material::VACUUM);
+    assert!(scattered < 1e-2);
+    assert!((total - 1.0).abs() < 1e-2);
+}
+
+#[test]
+fn test_dielectric() {
+    let (scattered, total) = run(1.0, Material::dielectric(4.0));
+    assert!(scattered < 1e-2);
+    assert!((total - 1.0).abs() < 1e-2);
+}
+
+/// A boundary on the first node leaves no room for the scattered field.
+#[test]
+fn test_first_node() {
+    assert!(matches!(
+        Tfsf1d::new(0, |_: f64| 0.0, Material::VACUUM),
+        Err(FDTDError::Region { .. })
+    ));
+}
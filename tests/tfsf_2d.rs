@@ -0,0 +1,98 @@
+// tests/tfsf_2d.rs
+/// Check that a 2D TFSF boundary introduces a plane wave within the box and
+/// nothing outside of it.
+use fdtd::error::FDTDError;
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension, Polarization};
+use fdtd::grid::Grid;
+use fdtd::tfsf::Tfsf2d;
+
+const SIZE_X: usize = 80;
+const SIZE_Y: usize = 60;
+const FIRST: (usize, usize) = (10, 10);
+const LAST: (usize, usize) = (69, 49);
+const STEPS: usize = 150;
+
+/// Return the largest Ez seen outside and inside of the box, after placing
+/// a PEC cylinder of the given radius at its center.
+fn run(radius: f64) -> (f64, f64) {
+    let mut g = Grid::new_2d(SIZE_X, SIZE_Y, None);
+    let (cx, cy) = (SIZE_X as f64 / 2.0, SIZE_Y as f64 / 2.0);
+    for m in 0..SIZE_X {
+        for n in 0..SIZE_Y {
+            let (x, y) = (m as f64 - cx, n as f64 - cy);
+            if x * x + y * y < radius * radius {
+                g.ceze[m * SIZE_Y + n] = 0.0;
+                g.cezh[m * SIZE_Y + n] = 0.0;
+            }
+        }
+    }
+
+    let pulse = |t: f64| (-((t - 40.0) / 10.0).powi(2)).exp();
+    let tfsf = Tfsf2d::new(&g, FIRST, LAST, pulse).unwrap();
+
+    let post_magnetic = |t: usize, g: &mut Grid| tfsf.magnetic(t, g);
+    let post_electric = |t: usize, g: &mut Grid| tfsf.electric(t, g);
+
+    let mut sim = FDTDSim::new(
+        Some(GridDimension::Two(Polarization::Magnetic)),
+        Some(Backend::Native),
+        Some(post_magnetic),
+        Some(post_electric),
+        None,
+    )
+    .unwrap();
+
+    let inside =
+        |m: usize, n: usize| (FIRST.0..=LAST.0).contains(&m) && (FIRST.1..=LAST.1).contains(&n);
+
+    let (mut scattered, mut total) = (0.0f64, 0.0f64);
+    for _ in 0..STEPS {
+        sim.step(&mut g).unwrap();
+
+        for m in 0..SIZE_X {
+            for n in 0..SIZE_Y {
+                let v = g.ez[m * SIZE_Y + n].abs();
+                if inside(m, n) {
+                    total = total.max(v);
+                } else {
+                    scattered = scattered.max(v);
+                }
+            }
+        }
+    }
+
+    (scattered, total)
+}
+
+#[test]
+fn test_no_scatterer() {
+    let (scattered, total) = run(0.0);
+    assert!(scattered < 1e-10);
+    assert!((total - 1.0).abs() < 0.05);
+}
+
+#[test]
+fn test_cylinder() {
+    let (scattered, _) = run(8.0);
+    assert!(scattered > 0.05);
+}
+
+/// Boxes which are empty or touch the edges of the grid are rejected.
+#[test]
+fn test_region() {
+    let g = Grid::new_2d(SIZE_X, SIZE_Y, None);
+    let pulse = |_: f64| 0.0;
+    for (first, last, reason) in [
+        ((0, 10), LAST, "edge"),
+        (FIRST, (69, SIZE_Y - 1), "edge"),
+        ((30, 10), (20, 49), "empty"),
+    ] {
+        match Tfsf2d::new(&g, first, last, pulse) {
+            Err(e @ FDTDError::Region { .. }) => {
+                assert!(e.to_string().contains(reason), "{}", e)
+            }
+            r => panic!("{:?}", r.map(|_| ())),
+        }
+    }
+    assert!(Tfsf2d::new(&g, FIRST, LAST, pulse).is_ok());
+}
@@ -0,0 +1,140 @@
+// tests/tfsf_3d.rs
+/// Check that a 3D TFSF boundary introduces a plane wave within the box and
+/// nothing outside of it.
+use fdtd::error::FDTDError;
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension};
+use fdtd::grid::{Field, Grid};
+use fdtd::tfsf::{Axis, Tfsf3d};
+
+const SIZE: [usize; 3] = [30, 26, 28];
+const FIRST: [usize; 3] = [5, 4, 6];
+const LAST: [usize; 3] = [24, 21, 20];
+const STEPS: usize = 90;
+
+/// Return the largest electric field seen outside and inside of the box,
+/// after placing a PEC sphere of the given radius at its center.
+fn run(propagation: Axis, polarization: Axis, radius: f64) -> (f64, f64) {
+    let mut g = Grid::new_3d(SIZE[0], SIZE[1], SIZE[2], None);
+    let idx = |m: usize, n: usize, p: usize| (m * SIZE[1] + n) * SIZE[2] + p;
+    let center = |a: usize| (FIRST[a] + LAST[a]) as f64 / 2.0;
+
+    for m in 0..SIZE[0] {
+        for n in 0..SIZE[1] {
+            for p in 0..SIZE[2] {
+                let (x, y, z) = (
+                    m as f64 - center(0),
+                    n as f64 - center(1),
+                    p as f64 - center(2),
+                );
+                if x * x + y * y + z * z < radius * radius {
+                    let i = idx(m, n, p);
+                    g.cexe[i] = 0.0;
+                    g.cexh[i] = 0.0;
+                    g.ceye[i] = 0.0;
+                    g.ceyh[i] = 0.0;
+                    g.ceze[i] = 0.0;
+                    g.cezh[i] = 0.0;
+                }
+            }
+        }
+    }
+
+    let pulse = |t: f64| (-((t - 40.0) / 8.0).powi(2)).exp();
+    let tfsf = Tfsf3d::new(&g, FIRST, LAST, propagation, polarization, pulse).unwrap();
+
+    let post_magnetic = |t: usize, g: &mut Grid| tfsf.magnetic(t, g);
+    let post_electric = |t: usize, g: &mut Grid| tfsf.electric(t, g);
+
+    let mut sim = FDTDSim::new(
+        Some(GridDimension::Three),
+        Some(Backend::Native),
+        Some(post_magnetic),
+        Some(post_electric),
+        None,
+    )
+    .unwrap();
+
+    // Every electric node on or within the faces of the box holds the
+    // total field; components sit half a cell along their own axis.
+    let inside = |f: Field, pos: [usize; 3]| {
+        let offset = f.offset();
+        (0..3).all(|a| {
+            let x = pos[a] as f64 + offset[a];
+            FIRST[a] as f64 <= x && x <= LAST[a] as f64
+        })
+    };
+
+    let (mut scattered, mut total) = (0.0f64, 0.0f64);
+    for _ in 0..STEPS {
+        sim.step(&mut g).unwrap();
+
+        for m in 0..SIZE[0] {
+            for n in 0..SIZE[1] {
+                for p in 0..SIZE[2] {
+                    let i = idx(m, n, p);
+                    for (f, e) in [(Field::Ex, &g.ex), (Field::Ey, &g.ey), (Field::Ez, &g.ez)] {
+                        let v = e[i].abs();
+                        if inside(f, [m, n, p]) {
+                            total = total.max(v);
+                        } else {
+                            scattered = scattered.max(v);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    (scattered, total)
+}
+
+#[test]
+fn test_no_scatterer() {
+    let directions = [
+        (Axis::X, Axis::Z),
+        (Axis::X, Axis::Y),
+        (Axis::Y, Axis::X),
+        (Axis::Z, Axis::Y),
+    ];
+
+    for (propagation, polarization) in directions {
+        let (scattered, total) = run(propagation, polarization, 0.0);
+        assert!(scattered < 1e-10);
+        assert!((total - 1.0).abs() < 0.05);
+    }
+}
+
+#[test]
+fn test_sphere() {
+    let (scattered, _) = run(Axis::X, Axis::Z, 5.0);
+    assert!(scattered > 0.01);
+}
+
+/// Boxes which are empty or touch the edges of the grid are rejected, as
+/// are waves polarized along their direction of propagation.
+#[test]
+fn test_region() {
+    let g = Grid::new_3d(SIZE[0], SIZE[1], SIZE[2], None);
+    let pulse = |_: f64| 0.0;
+    let new =
+        |first, last, polarization| Tfsf3d::new(&g, first, last, Axis::X, polarization, pulse);
+
+    for (first, last, reason) in [
+        ([5, 4, 0], LAST, "edge"),
+        (FIRST, [24, 21, SIZE[2] - 1], "edge"),
+        (FIRST, [24, 3, 20], "empty"),
+    ] {
+        match new(first, last, Axis::Z) {
+            Err(e @ FDTDError::Region { .. }) => {
+                assert!(e.to_string().contains(reason), "{}", e)
+            }
+            r => panic!("{:?}", r.map(|_| ())),
+        }
+    }
+
+    assert!(matches!(
+        new(FIRST, LAST, Axis::X),
+        Err(FDTDError::Polarization { axis: Axis::X })
+    ));
+    assert!(new(FIRST, LAST, Axis::Z).is_ok());
+}
@@ -1,6 +1,7 @@
 // tests/create_grid.rs
 use fdtd::fdtd::{GridDimension, Polarization};
 use fdtd::grid::Grid;
+use fdtd::material::Material;
 use rand::thread_rng;
 use rand::Rng;
 
@@ -16,13 +17,15 @@ pub fn default_grid(
         GridDimension::Two(Polarization::Magnetic) => {
             Grid::new_2d(x_sz, y_sz.expect("Need 'y_sz' for 2 dimensions."), None)
         }
+        GridDimension::Two(Polarization::Electric) => {
+            Grid::new_2d_te(x_sz, y_sz.expect("Need 'y_sz' for 2 dimensions."), None)
+        }
         GridDimension::Three => Grid::new_3d(
             x_sz,
             y_sz.expect("Need 'y_sz' for 3 dimensions."),
             z_sz.expect("Need 'z_sz' for 3 dimensions."),
             None,
         ),
-        _ => panic!("Unimplemented!"),
     }
 }
 
@@ -39,13 +42,15 @@ pub fn precomputed_grid(
         GridDimension::Two(Polarization::Magnetic) => {
             Grid::new_2d(x_sz, y_sz.expect("Need 'y_sz' for 2 dimensions."), None)
         }
+        GridDimension::Two(Polarization::Electric) => {
+            Grid::new_2d_te(x_sz, y_sz.expect("Need 'y_sz' for 2 dimensions."), None)
+        }
         GridDimension::Three => Grid::new_3d(
             x_sz,
             y_sz.expect("Need 'y_sz' for 3 dimensions."),
             z_sz.expect("Need 'z_sz' for 3 dimensions."),
             None,
         ),
-        _ => panic!("Unimplemented!"),
     }
 }
 
@@ -62,49 +67,39 @@ pub fn random_grid(
         GridDimension::Two(Polarization::Magnetic) => {
             Grid::new_2d(x_sz, y_sz.expect("Need 'y_sz' for 2 dimensions."), None)
         }
+        GridDimension::Two(Polarization::Electric) => {
+            Grid::new_2d_te(x_sz, y_sz.expect("Need 'y_sz' for 2 dimensions."), None)
+        }
         GridDimension::Three => Grid::new_3d(
             x_sz,
             y_sz.expect("Need 'y_sz' for 3 dimensions."),
             z_sz.expect("Need 'z_sz' for 3 dimensions."),
             None,
         ),
-        _ => panic!("Unimplemented!"),
-    };
-
-    let iter_len = match grid_type {
-        GridDimension::One => x_sz,
-        GridDimension::Two(_) => x_sz * y_sz.expect("Need 'y_sz' for 2 dimensions."),
-        GridDimension::Three => {
-            x_sz * y_sz.expect("Need 'y_sz' for 2 dimensions.")
-                * z_sz.expect("Need 'z_sz' for 2 dimensions.")
-        }
     };
 
-    for i in 0..iter_len {
-        if let GridDimension::Three = grid_type {
-            g.ex[i] = rng.gen_range(-10.0..10.0);
-            g.cexh[i] = rng.gen_range(-10.0..10.0);
-            g.cexe[i] = rng.gen_range(-10.0..10.0);
-            g.ey[i] = rng.gen_range(-10.0..10.0);
-            g.ceyh[i] = rng.gen_range(-10.0..10.0);
-            g.ceye[i] = rng.gen_range(-10.0..10.0);
+    // Random lossy materials keep the coefficients of each node physical,
+    // so that the grid passes validation; the fields are arbitrary.
+    for x in 0..g.x_sz {
+        for y in 0..g.y_sz.max(1) {
+            for z in 0..g.z_sz.max(1) {
+                let m = Material::matched(
+                    rng.gen_range(1.0..10.0),
+                    rng.gen_range(1.0..10.0),
+                    rng.gen_range(0.0..0.001),
+                );
+                g.set_material([x, y, z], &m);
+            }
         }
+    }
 
-        if let GridDimension::Two(_) | GridDimension::Three = grid_type {
-            g.hx[i] = rng.gen_range(-10.0..10.0);
-            g.chxh[i] = rng.gen_range(-10.0..10.0);
-            g.chxe[i] = rng.gen_range(-10.0..10.0);
-            g.hy[i] = rng.gen_range(-10.0..10.0);
-            g.chyh[i] = rng.gen_range(-10.0..10.0);
-            g.chye[i] = rng.gen_range(-10.0..10.0);
+    // Components the grid doesn't have are left empty.
+    for field in [
+        &mut g.hx, &mut g.hy, &mut g.hz, &mut g.ex, &mut g.ey, &mut g.ez,
+    ] {
+        for v in field.iter_mut() {
+            *v = rng.gen_range(-10.0..10.0);
         }
-
-        g.hy[i] = rng.gen_range(-10.0..10.0);
-        g.chyh[i] = rng.gen_range(-10.0..10.0);
-        g.chye[i] = rng.gen_range(-10.0..10.0);
-        g.ez[i] = rng.gen_range(-10.0..10.0);
-        g.cezh[i] = rng.gen_range(-10.0..10.0);
-        g.ceze[i] = rng.gen_range(-10.0..10.0);
     }
 
     g
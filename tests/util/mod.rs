@@ -1,2 +1,7 @@
+// Each test only uses some of these.
+#![allow(dead_code)]
+
 pub mod create_grid;
 pub mod grid_eq;
+pub mod record;
+pub mod sim;
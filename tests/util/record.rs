@@ -0,0 +1,12 @@
+// util/record.rs
+
+/// Return the largest error between the two records, relative to the peak of
+/// the reference.
+pub fn relative_error(a: &[f64], reference: &[f64]) -> f64 {
+    let peak = reference.iter().fold(0.0f64, |acc, v| acc.max(v.abs()));
+    let err = a
+        .iter()
+        .zip(reference)
+        .fold(0.0f64, |acc, (a, b)| acc.max((a - b).abs()));
+    err / peak
+}
@@ -0,0 +1,11 @@
+// util/sim.rs
+use fdtd::fdtd::{Backend, FDTDSim, GridDimension};
+use fdtd::grid::Grid;
+
+/// A simulation without any post-{magnetic, electric} functions.
+pub type Sim = FDTDSim<fn(usize, &mut Grid), fn(usize, &mut Grid)>;
+
+/// Build a native simulation without any post-{magnetic, electric} functions.
+pub fn sim(dimension: GridDimension) -> Sim {
+    FDTDSim::new(Some(dimension), Some(Backend::Native), None, None, None).unwrap()
+}
@@ -0,0 +1,143 @@
+// tests/validate.rs
+/// Check grids and simulations are validated against the Courant limit and
+/// their update coefficients.
+use fdtd::error::FDTDError;
+use fdtd::fdtd::{GridDimension, Polarization};
+use fdtd::grid::{Field, Grid};
+use fdtd::material::Material;
+use fdtd::sources::PointSource;
+
+mod util;
+use util::sim::sim;
+
+/// Grids are built at their Courant limit by default, and can't be built
+/// beyond it.
+#[test]
+fn test_courant_limit() {
+    let g = Grid::new_1d(10);
+    assert_eq!(g.cdtds, 1.0);
+    assert_eq!(g.courant_limit(), 1.0);
+    assert_eq!(Grid::new_2d(4, 4, None).cdtds, 1.0 / 2.0f64.sqrt());
+    assert_eq!(
+        Grid::new_3d(4, 4, 4, None).courant_limit(),
+        1.0 / 3.0f64.sqrt()
+    );
+    assert!(Grid::try_new_2d_te(4, 4, Some(1.0 / 2.0f64.sqrt())).is_ok());
+    assert!(Grid::try_new_3d(4, 4, 4, Some(0.5)).is_ok());
+
+    match Grid::try_new_2d(4, 4, Some(0.8)) {
+        Err(FDTDError::Courant { cdtds, limit }) => {
+            assert_eq!(cdtds, 0.8);
+            assert_eq!(limit, 1.0 / 2.0f64.sqrt());
+        }
+        r => panic!("{:?}", r.map(|_| ())),
+    }
+    assert!(matches!(
+        Grid::try_new_3d(4, 4, 4, Some(0.6)),
+        Err(FDTDError::Courant { .. })
+    ));
+    assert!(matches!(
+        Grid::try_new_2d(4, 4, Some(-0.1)),
+        Err(FDTDError::Courant { .. })
+    ));
+}
+
+/// Grids built without the check are only rejected once they're stepped.
+#[test]
+fn test_courant_unchecked() {
+    let mut g = Grid::new_2d_te(4, 4, Some(0.8));
+    assert_eq!(g.cdtds, 0.8);
+
+    let mut s = sim(GridDimension::Two(Polarization::Electric));
+    assert!(matches!(s.step(&mut g), Err(FDTDError::Courant { .. })));
+}
+
+/// Coefficients that would make the update unstable are found, along with
+/// their position.
+#[test]
+fn test_coefficients() {
+    const Y_SZ: usize = 5;
+    const I: usize = 3 * Y_SZ + 2;
+    let fresh = || {
+        let mut g = Grid::new_2d(6, Y_SZ, None);
+        g.set_materials(|[x, _, _]| Material::dielectric(1.0 + x as f64));
+        g
+    };
+    assert!(fresh().validate().is_ok());
+
+    let cases: [(fn(&mut Grid), Field, &str); 4] = [
+        (|g| g.ceze[I] = 1.5, Field::Ez, "magnitude"),
+        (|g| g.chxe[I] = -1.0, Field::Hx, "negative"),
+        (|g| g.chyh[I] = f64::NAN, Field::Hy, "finite"),
+        (
+            |g| g.set_material([3, 2, 0], &Material::dielectric(0.25)),
+            Field::Ez,
+            "local Courant",
+        ),
+    ];
+    for (change, field, reason) in cases {
+        let mut g = fresh();
+        change(&mut g);
+        match g.validate() {
+            Err(e @ FDTDError::Coefficient { .. }) => {
+                let message = e.to_string();
+                assert!(message.contains(reason), "{}", message);
+                if let FDTDError::Coefficient { field: f, pos, .. } = e {
+                    assert_eq!((f, pos), (field, [3, 2, 0]));
+                }
+            }
+            r => panic!("{:?}", r),
+        }
+    }
+
+    let mut g = fresh();
+    g.hy.pop();
+    assert!(matches!(
+        g.validate(),
+        Err(FDTDError::Length {
+            field: Field::Hy,
+            len: 29,
+            expected: 30
+        })
+    ));
+}
+
+/// Simulations only step grids that match their dimension, within the
+/// Courant limit and with valid coefficients, with sources on nodes of the
+/// grid.
+#[test]
+fn test_sim() {
+    let mut g = Grid::new_2d(10, 10, None);
+    for dimension in [
+        GridDimension::One,
+        GridDimension::Two(Polarization::Electric),
+        GridDimension::Three,
+    ] {
+        match sim(dimension).step(&mut g) {
+            Err(FDTDError::Dimension { sizes, .. }) => assert_eq!(sizes, [10, 10, 0]),
+            r => panic!("{:?}", r),
+        }
+    }
+
+    let mut s = sim(GridDimension::Two(Polarization::Magnetic));
+    s.step(&mut g).unwrap();
+    g.ceze[5] = 1.5;
+    assert!(matches!(
+        s.step(&mut g),
+        Err(FDTDError::Coefficient {
+            field: Field::Ez,
+            ..
+        })
+    ));
+    g.ceze[5] = 1.0;
+    g.cdtds = 0.9;
+    assert!(matches!(s.step(&mut g), Err(FDTDError::Courant { .. })));
+
+    let mut g = Grid::new_1d(10);
+    let mut s = sim(GridDimension::One);
+    assert!(s.step_mul(&mut g, 5).is_ok());
+
+    // Sources added after the first step are still checked.
+    s.add_source(PointSource::soft(Field::Ez, [10, 0, 0], |_| 0.0));
+    assert!(matches!(s.step(&mut g), Err(FDTDError::Node { .. })));
+}
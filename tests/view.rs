@@ -0,0 +1,85 @@
+// tests/view.rs
+/// Check views of field components agree with the layout of the field
+/// vectors, and are bounds-checked.
+use fdtd::grid::{Field, Grid};
+
+/// Values of a 3D grid's component, numbered by their index.
+fn numbered() -> Grid {
+    let mut g = Grid::new_3d(4, 5, 6, None);
+    for (i, v) in g.ex.iter_mut().enumerate() {
+        *v = i as f64;
+    }
+    g
+}
+
+#[test]
+fn test_layout() {
+    let g = numbered();
+    let ex = g.view(Field::Ex).unwrap();
+    assert_eq!(ex.shape(), [4, 5, 6]);
+    assert_eq!(ex[[2, 3, 4]], ((2 * 5 + 3) * 6 + 4) as f64);
+    assert_eq!(g.get(Field::Ex, [3, 4, 5]), Some(119.0));
+
+    // Planes at each x and rows along z are contiguous.
+    assert_eq!(ex.plane(1), &g.ex[30..60]);
+    assert_eq!(ex.row(1, 2), &g.ex[42..48]);
+
+    let positions: Vec<[usize; 3]> = g.positions().collect();
+    assert_eq!(positions.len(), g.ex.len());
+    for ((pos, v), p) in ex.iter().zip(&positions) {
+        assert_eq!(pos, *p);
+        assert_eq!(ex[pos], v);
+    }
+
+    // Axes a grid doesn't have are one node long.
+    let g = Grid::new_2d(7, 3, None);
+    let ez = g.view(Field::Ez).unwrap();
+    assert_eq!(ez.shape(), [7, 3, 1]);
+    assert_eq!(ez.plane(6).len(), 3);
+    assert_eq!(g.positions().nth(4), Some([1, 1, 0]));
+}
+
+#[test]
+fn test_bounds() {
+    let mut g = Grid::new_2d(7, 3, None);
+    assert_eq!(g.get(Field::Ez, [7, 0, 0]), None);
+    assert_eq!(g.get(Field::Ez, [0, 3, 0]), None);
+    assert_eq!(g.get(Field::Ez, [0, 0, 1]), None);
+    assert!(g.get_mut(Field::Ez, [6, 3, 0]).is_none());
+
+    // Components the grid doesn't have have no view.
+    assert!(g.view(Field::Ex).is_none());
+    assert!(g.view_mut(Field::Hz).is_none());
+    assert_eq!(g.get(Field::Ey, [0, 0, 0]), None);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_index_panic() {
+    let g = Grid::new_1d(10);
+    let _ = g.view(Field::Ez).unwrap()[[10, 0, 0]];
+}
+
+#[test]
+fn test_mutate() {
+    let mut g = Grid::new_3d(4, 5, 6, None);
+    *g.get_mut(Field::Hy, [1, 2, 3]).unwrap() = 1.0;
+    assert_eq!(g.hy[(5 + 2) * 6 + 3], 1.0);
+
+    let mut hz = g.view_mut(Field::Hz).unwrap();
+    hz[[3, 0, 1]] = 2.0;
+    hz.row_mut(0, 4).fill(3.0);
+    hz.plane_mut(2).fill(4.0);
+    for (pos, v) in hz.iter_mut() {
+        if pos == [1, 1, 1] {
+            *v = 5.0;
+        }
+    }
+
+    let hz = g.view(Field::Hz).unwrap();
+    assert_eq!(hz[[3, 0, 1]], 2.0);
+    assert!(hz.row(0, 4).iter().all(|v| *v == 3.0));
+    assert_eq!(hz[[2, 4, 5]], 4.0);
+    assert_eq!(hz[[1, 1, 1]], 5.0);
+    assert_eq!(g.hz.iter().filter(|v| **v != 0.0).count(), 1 + 6 + 30 + 1);
+}
@@ -0,0 +1,85 @@
+// tests/watchdog.rs
+/// Check the watchdog halts unstable simulations, and only those.
+use fdtd::error::FDTDError;
+use fdtd::fdtd::{GridDimension, Polarization};
+use fdtd::grid::{Field, Grid};
+use fdtd::sources::PointSource;
+use fdtd::watchdog::Watchdog;
+use fdtd::waveform::Gaussian;
+
+mod util;
+use util::sim::Sim;
+
+fn sim(dimension: GridDimension, watchdog: Watchdog) -> Sim {
+    let mut s = util::sim::sim(dimension);
+    s.set_watchdog(Some(watchdog));
+    s
+}
+
+/// A value which isn't finite is named by step, component and position.
+#[test]
+fn test_not_finite() {
+    let mut g = Grid::new_1d(100);
+    let mut s = sim(GridDimension::One, Watchdog::new(1));
+    s.add_source(PointSource::soft(Field::Ez, [50, 0, 0], |_| f64::NAN));
+    match s.step_mul(&mut g, 10) {
+        Err(FDTDError::Unstable {
+            step,
+            field,
+            pos,
+            value,
+        }) => {
+            assert_eq!((step, field, pos), (1, Field::Ez, [50, 0, 0]));
+            assert!(value.is_nan());
+        }
+        r => panic!("{:?}", r),
+    }
+
+    // Checks fall on multiples of the interval, however the steps are
+    // taken.
+    let mut g = Grid::new_2d(20, 20, None);
+    let mut s = sim(GridDimension::Two(Polarization::Magnetic), Watchdog::new(4));
+    s.step_mul(&mut g, 3).unwrap();
+    g.hx[5 * 20 + 7] = f64::INFINITY;
+    let e = s.step(&mut g).unwrap_err();
+    assert!(matches!(
+        e,
+        FDTDError::Unstable {
+            step: 4,
+            field: Field::Hx,
+            pos: [5, 7, 0],
+            ..
+        }
+    ));
+    assert!(e.to_string().contains("after 4 steps"));
+}
+
+/// Fields grown by a source are caught before they overflow, while a pulse
+/// switching on and spreading isn't mistaken for growth.
+#[test]
+fn test_growth() {
+    let dimension = GridDimension::Two(Polarization::Magnetic);
+    let watchdog = Watchdog::new(10).with_growth(2.0, 3);
+
+    let mut g = Grid::new_2d(60, 60, None);
+    let mut s = sim(dimension, watchdog.clone());
+    s.add_source(PointSource::soft(
+        Field::Ez,
+        [30, 30, 0],
+        Gaussian::new(30.0, 10.0),
+    ));
+    s.step_mul(&mut g, 300).unwrap();
+
+    let mut g = Grid::new_2d(60, 60, None);
+    let mut s = sim(dimension, watchdog);
+    s.add_source(PointSource::soft(Field::Ez, [30, 30, 0], |t: f64| {
+        1.2f64.powf(t)
+    }));
+    match s.step_mul(&mut g, 1000) {
+        Err(FDTDError::Unstable { step, value, .. }) => {
+            assert!(step % 10 == 0 && step <= 100, "{}", step);
+            assert!(value.is_finite());
+        }
+        r => panic!("{:?}", r),
+    }
+}
@@ -0,0 +1,87 @@
+// tests/waveform.rs
+/// Sanity checks for the waveform library.
+use fdtd::ricker::ricker;
+use fdtd::waveform::{
+    DiffGaussian, Gaussian, ModulatedGaussian, Ricker, Sampled, Sinusoid, Waveform,
+};
+
+/// Return the largest magnitude of 'w' over the first 'steps' steps,
+/// sampled ten times per step.
+fn peak(w: &impl Waveform, steps: usize) -> f64 {
+    (0..steps * 10)
+        .map(|i| w.value(i as f64 / 10.0).abs())
+        .fold(0.0, f64::max)
+}
+
+#[test]
+fn test_ricker() {
+    let cdtds = 1.0 / 2.0f64.sqrt();
+    let w = Ricker::new(cdtds, 20.0, 3.0);
+
+    for q in 0..100 {
+        assert_eq!(w.step(q), ricker(q as f64, 3.0, cdtds, 20.0));
+        assert_eq!(w.half_step(q), ricker(q as f64 + 0.5, 3.0, cdtds, 20.0));
+    }
+}
+
+#[test]
+fn test_gaussian() {
+    let w = Gaussian::new(30.0, 10.0);
+    assert_eq!(w.step(30), 1.0);
+    assert_eq!(w.step(20), w.step(40));
+    assert!((w.step(40) - (-1.0f64).exp()).abs() < 1e-15);
+}
+
+#[test]
+fn test_diff_gaussian() {
+    let w = DiffGaussian::new(30.0, 10.0);
+    assert_eq!(w.step(30), 0.0);
+    assert!((peak(&w, 100) - 1.0).abs() < 1e-3);
+
+    // No DC component.
+    let sum: f64 = (0..=60).map(|q| w.step(q)).sum();
+    assert!(sum.abs() < 1e-6);
+}
+
+#[test]
+fn test_modulated_gaussian() {
+    let w = ModulatedGaussian::new(50.0, 15.0, 0.05);
+    let envelope = Gaussian::new(50.0, 15.0);
+
+    assert!(peak(&w, 100) <= 1.0);
+    for q in 0..100 {
+        assert!(w.step(q).abs() <= envelope.step(q));
+    }
+}
+
+#[test]
+fn test_sinusoid() {
+    let w = Sinusoid::new(0.05, 40.0);
+    assert_eq!(w.value(-1.0), 0.0);
+    assert!(w.step(10).abs() < (0.1 * std::f64::consts::PI * 10.0).sin().abs());
+
+    // After the ramp, a plain sinusoid.
+    for q in 40..100 {
+        let expected = (2.0 * std::f64::consts::PI * 0.05 * q as f64).sin();
+        assert!((w.step(q) - expected).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_sampled() {
+    let w = Sampled::new(vec![0.0, 1.0, 3.0]);
+    assert_eq!(w.step(1), 1.0);
+    assert_eq!(w.step(2), 3.0);
+    assert_eq!(w.half_step(0), 0.5);
+    assert_eq!(w.half_step(1), 2.0);
+    assert_eq!(w.half_step(2), 0.0);
+    assert_eq!(w.value(-0.5), 0.0);
+    assert_eq!(Sampled::new(Vec::new()).step(0), 0.0);
+}
+
+#[test]
+fn test_closure() {
+    let w = |t: f64| 2.0 * t;
+    assert_eq!(w.step(3), 6.0);
+    assert_eq!(w.half_step(3), 7.0);
+}